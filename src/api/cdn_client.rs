@@ -1,6 +1,8 @@
 use anyhow::Result;
 use iced_native::image;
 
+use crate::data::snowflake::Snowflake;
+
 const CDN_BASE_URL: &str = "https://cdn.discordapp.com";
 
 #[derive(Default, Clone)]
@@ -15,7 +17,12 @@ impl CdnClient {
         }
     }
 
-    pub async fn avatar(self, user_id: String, avatar: String, size: u16) -> Result<image::Handle> {
+    pub async fn avatar(
+        self,
+        user_id: Snowflake,
+        avatar: String,
+        size: u16,
+    ) -> Result<image::Handle> {
         let data = self
             .client
             .get(format!(
@@ -46,7 +53,7 @@ impl CdnClient {
 
     pub async fn channel_icon(
         self,
-        channel_id: String,
+        channel_id: Snowflake,
         icon: String,
         size: u16,
     ) -> Result<image::Handle> {
@@ -62,4 +69,36 @@ impl CdnClient {
 
         Ok(image::Handle::from_memory(data.to_vec()))
     }
+
+    pub async fn guild_icon(
+        self,
+        guild_id: Snowflake,
+        icon: String,
+        size: u16,
+    ) -> Result<image::Handle> {
+        let data = self
+            .client
+            .get(format!(
+                "{CDN_BASE_URL}/icons/{guild_id}/{icon}.png?size={size}"
+            ))
+            .send()
+            .await?
+            .bytes()
+            .await?;
+
+        Ok(image::Handle::from_memory(data.to_vec()))
+    }
+
+    /// Animated emojis are loaded as a still image
+    pub async fn emoji(self, emoji_id: Snowflake, size: u16) -> Result<image::Handle> {
+        let data = self
+            .client
+            .get(format!("{CDN_BASE_URL}/emojis/{emoji_id}.png?size={size}"))
+            .send()
+            .await?
+            .bytes()
+            .await?;
+
+        Ok(image::Handle::from_memory(data.to_vec()))
+    }
 }
@@ -1,11 +1,15 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, iter};
 
 use serde::Deserialize;
 use serde_json::Value;
+use time::OffsetDateTime;
 
 use crate::data::{
+    guild::{Emoji, Guild, Member, Role},
+    message::{Attachment, Embed, Message, MessageFlags, ReactionEmoji},
+    snowflake::Snowflake,
     state::{PrivateChannel, PrivateChannelKind, Relationship, RelationshipKind, State},
-    user::{Presence, User},
+    user::{Activity, ClientStatus, CustomStatus, Presence, User},
 };
 
 #[derive(Debug, Clone, Deserialize)]
@@ -25,6 +29,14 @@ pub struct DispatchReady {
     pub relationships: Vec<RelationshipData>,
     pub private_channels: Vec<PrivateChannelData>,
     pub presences: Vec<PresenceData>,
+    #[serde(default)]
+    pub guilds: Vec<DispatchGuildCreate>,
+    /// Members of the current user for each guild, in the same order as `guilds`
+    #[serde(default)]
+    pub merged_members: Vec<Vec<MemberData>>,
+    /// Only sent if the client does not request the settings as protobuf
+    pub user_settings: Option<UserSettingsData>,
+    pub read_state: Option<ReadStatesData>,
     pub resume_gateway_url: String,
     pub session_id: String,
 }
@@ -36,15 +48,8 @@ impl Into<State> for DispatchReady {
             .relationships
             .iter()
             .map(|r| Relationship {
-                id: r.user.id.clone(),
-                kind: match r.kind {
-                    1 => RelationshipKind::Friend,
-                    2 => RelationshipKind::Blocked,
-                    3 => RelationshipKind::PendingIncoming,
-                    4 => RelationshipKind::PendingOutgoing,
-                    5 => RelationshipKind::Implicit,
-                    _ => RelationshipKind::None,
-                },
+                id: r.user.id,
+                kind: RelationshipKind::from(r.kind),
             })
             .collect();
 
@@ -63,36 +68,106 @@ impl Into<State> for DispatchReady {
                 name: c.name,
                 icon: c.icon,
                 icon_handle: None,
-                last_message_timestamp: (c.last_message_id.parse().unwrap_or(0) >> 22)
-                    + 1420070400000,
+                last_message_id: c.last_message_id,
             })
             .collect::<Vec<_>>();
 
-        private_channels.sort_by(|a, b| b.last_message_timestamp.cmp(&a.last_message_timestamp));
+        // Channels without messages are sorted last
+        private_channels.sort_by(|a, b| b.last_message_id.cmp(&a.last_message_id));
 
         // Put all known users (from relationships) into a HashMap
         let mut user_cache = self
             .relationships
             .into_iter()
-            .map(|r| (r.user.id.clone(), r.user))
-            .collect::<HashMap<String, User>>();
+            .map(|r| (r.user.id, r.user))
+            .collect::<HashMap<Snowflake, User>>();
 
-        let user_id = self.user.id.clone();
-        user_cache.insert(self.user.id.clone(), self.user);
+        let user_id = self.user.id;
+        user_cache.insert(self.user.id, self.user);
+
+        // Unavailable guilds are kept so they can be replaced once they are sent with GUILD_CREATE
+        let guilds = self
+            .guilds
+            .into_iter()
+            .zip(self.merged_members.into_iter().chain(iter::repeat(vec![])))
+            .map(|(mut guild, members)| {
+                guild.members.extend(members);
+                guild.into_guild(user_id)
+            })
+            .collect();
 
         // Update users presences
         self.presences.into_iter().for_each(|p| {
             if let Some(user) = user_cache.get_mut(&p.user.id) {
-                user.presence = match p.status.as_str() {
-                    "online" => Presence::Online,
-                    "idle" => Presence::Idle,
-                    "dnd" => Presence::DoNotDisturb,
-                    _ => Presence::Offline,
-                }
+                p.apply(user);
             }
         });
 
-        State::new(user_id, relationships, private_channels, user_cache)
+        // The own presence is restored from the settings, it is not part of `presences`
+        let (presence, custom_status) = self
+            .user_settings
+            .map_or((None, None), |s| (s.status, s.custom_status));
+        if let (Some(presence), Some(user)) = (presence, user_cache.get_mut(&user_id)) {
+            user.presence = presence;
+        }
+
+        let mut state = State::new(user_id, relationships, private_channels, guilds, user_cache);
+        state.custom_status = custom_status
+            .and_then(|s| s.into_custom_status())
+            .filter(|s| !s.is_expired());
+
+        let read_states = match self.read_state {
+            Some(ReadStatesData::Versioned { entries }) | Some(ReadStatesData::List(entries)) => {
+                entries
+            }
+            None => vec![],
+        };
+        state.read_states = read_states
+            .into_iter()
+            .filter_map(|r| Some((r.id, r.last_message_id?)))
+            .collect();
+
+        state
+    }
+}
+
+/// Versioned read states are sent as an object, older ones as a list
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ReadStatesData {
+    Versioned { entries: Vec<ReadStateData> },
+    List(Vec<ReadStateData>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadStateData {
+    /// Channel id
+    pub id: Snowflake,
+    /// Not set for read states of other things than channels
+    #[serde(default)]
+    pub last_message_id: Option<Snowflake>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserSettingsData {
+    /// Saved presence, invisible users are `Offline`
+    pub status: Option<Presence>,
+    pub custom_status: Option<CustomStatusData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CustomStatusData {
+    pub text: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl CustomStatusData {
+    fn into_custom_status(self) -> Option<CustomStatus> {
+        Some(CustomStatus {
+            text: self.text.filter(|t| !t.is_empty())?,
+            expires_at: self.expires_at,
+        })
     }
 }
 
@@ -105,28 +180,285 @@ pub struct RelationshipData {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PrivateChannelData {
-    pub id: String,
+    pub id: Snowflake,
     #[serde(rename = "type")]
     pub kind: u16,
     pub recipients: Vec<User>,
     pub name: Option<String>,
     pub icon: Option<String>,
-    pub owner_id: Option<String>,
-    pub last_message_id: String,
+    pub owner_id: Option<Snowflake>,
+    pub last_message_id: Option<Snowflake>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PresenceData {
+    /// Only contains the id unless the user itself changed
+    pub user: PartialUserData,
+    pub status: Presence,
+    #[serde(default)]
+    pub client_status: ClientStatus,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+}
+
+impl PresenceData {
+    pub fn apply(self, user: &mut User) {
+        user.presence = self.status;
+        user.client_status = self.client_status;
+        user.activities = self.activities;
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionData {
+    pub session_id: String,
+    pub status: Presence,
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+    pub client_info: ClientInfo,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientInfo {
+    /// desktop, mobile, web or unknown
+    pub client: String,
+}
+
+/// All sessions of the current user. Sent whenever one of them connects, disconnects or changes its status
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchSessionsReplace(pub Vec<SessionData>);
+
+impl DispatchSessionsReplace {
+    pub fn apply(self, user: &mut User) {
+        let mut client_status = ClientStatus::default();
+        for session in &self.0 {
+            match session.client_info.client.as_str() {
+                "desktop" => client_status.desktop = Some(session.status),
+                "mobile" => client_status.mobile = Some(session.status),
+                "web" => client_status.web = Some(session.status),
+                _ => {}
+            }
+        }
+
+        // The session with the id "all" combines the status of all sessions
+        if let Some(session) =
+            self.0
+                .into_iter()
+                .reduce(|all, s| if s.session_id == "all" { s } else { all })
+        {
+            user.presence = session.status;
+            user.activities = session.activities;
+        } else {
+            user.presence = Presence::Offline;
+            user.activities.clear();
+        }
+        user.client_status = client_status;
+    }
+}
+
+/// Sent when a user adds or removes a reaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageReaction {
+    pub user_id: Snowflake,
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+    pub emoji: ReactionEmoji,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageReactionRemoveAll {
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageReactionRemoveEmoji {
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+    pub emoji: ReactionEmoji,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchTypingStart {
+    pub channel_id: Snowflake,
+    pub user_id: Snowflake,
+    /// Only sent in guilds
+    pub member: Option<TypingMemberData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TypingMemberData {
+    pub user: User,
+}
+
+/// Sent when a channel was read on another client
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageAck {
+    pub channel_id: Snowflake,
+    pub message_id: Snowflake,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageDelete {
+    pub id: Snowflake,
+    pub channel_id: Snowflake,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageDeleteBulk {
+    pub ids: Vec<Snowflake>,
+    pub channel_id: Snowflake,
+}
+
+/// Message updates only contain the fields that changed
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchMessageUpdate {
+    pub id: Snowflake,
+    pub channel_id: Snowflake,
+    pub content: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub edited_timestamp: Option<OffsetDateTime>,
+    pub attachments: Option<Vec<Attachment>>,
+    pub embeds: Option<Vec<Embed>>,
+    pub mentions: Option<Vec<User>>,
+    pub mention_roles: Option<Vec<Snowflake>>,
+    pub mention_everyone: Option<bool>,
+    pub pinned: Option<bool>,
+    pub flags: Option<MessageFlags>,
+}
+
+impl DispatchMessageUpdate {
+    pub fn apply(self, msg: &mut Message) {
+        if let Some(content) = self.content {
+            msg.content = content;
+        }
+        if self.edited_timestamp.is_some() {
+            msg.edited_timestamp = self.edited_timestamp;
+        }
+        if let Some(attachments) = self.attachments {
+            msg.attachments = attachments;
+        }
+        if let Some(embeds) = self.embeds {
+            msg.embeds = embeds;
+        }
+        if let Some(mentions) = self.mentions {
+            msg.mentions = mentions.into_iter().map(|u| u.id).collect();
+        }
+        if let Some(mention_roles) = self.mention_roles {
+            msg.mention_roles = mention_roles;
+        }
+        if let Some(mention_everyone) = self.mention_everyone {
+            msg.mention_everyone = mention_everyone;
+        }
+        if let Some(pinned) = self.pinned {
+            msg.pinned = pinned;
+        }
+        if let Some(flags) = self.flags {
+            msg.flags = flags;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PartialUserData {
+    pub id: Snowflake,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberData {
+    /// Only set for merged members in READY
+    pub user_id: Option<Snowflake>,
+    pub user: Option<PartialUserData>,
+    pub nick: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<Snowflake>,
+}
+
+impl MemberData {
+    pub fn user_id(&self) -> Option<Snowflake> {
+        self.user_id.or(self.user.as_ref().map(|u| u.id))
+    }
+}
+
+impl Into<Member> for MemberData {
+    fn into(self) -> Member {
+        Member {
+            nick: self.nick,
+            roles: self.roles,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchGuildCreate {
+    #[serde(flatten)]
+    pub guild: Guild,
+    #[serde(default)]
+    pub members: Vec<MemberData>,
+}
+
+impl DispatchGuildCreate {
+    /// Caches the members that were sent with the guild, including the current user
+    pub fn into_guild(self, user_id: Snowflake) -> Guild {
+        let mut guild = self.guild;
+        guild.members = self
+            .members
+            .into_iter()
+            .filter_map(|m| Some((m.user_id()?, m.into())))
+            .collect();
+        guild.member = guild.members.get(&user_id).cloned();
+
+        guild
+    }
+}
+
+/// Guild updates do not contain channels or members
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchGuildUpdate {
+    pub id: Snowflake,
+    pub name: String,
+    pub icon: Option<String>,
+    pub owner_id: Option<Snowflake>,
+    pub roles: Option<Vec<Role>>,
+    pub emojis: Option<Vec<Emoji>>,
+}
+
+impl DispatchGuildUpdate {
+    pub fn apply(self, guild: &mut Guild) {
+        guild.name = self.name;
+        if guild.icon != self.icon {
+            guild.icon = self.icon;
+            guild.icon_handle = None;
+        }
+        if self.owner_id.is_some() {
+            guild.owner_id = self.owner_id;
+        }
+        if let Some(roles) = self.roles {
+            guild.roles = roles;
+        }
+        if let Some(emojis) = self.emojis {
+            guild.emojis = emojis;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchGuildDelete {
+    pub id: Snowflake,
+    /// Set if the guild became unavailable because of an outage, otherwise the user left the guild
+    #[serde(default)]
+    pub unavailable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatchRelationshipAdd {
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub kind: u16,
     pub user: User,
-    pub status: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
-pub struct DispatchMessage {
-    pub id: String,
-    pub channel_id: String,
-    pub author: User,
-    pub content: String,
-    pub timestamp: String,
-    pub edited_timestamp: Option<String>,
+pub struct DispatchRelationshipRemove {
+    pub id: Snowflake,
 }
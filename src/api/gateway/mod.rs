@@ -33,13 +33,22 @@ use tokio_tungstenite::{
 use tracing::{error, info, warn};
 
 use crate::{
-    api::gateway::{data::DispatchMessage, payloads::identify_payload},
-    data::state::State,
+    api::{gateway::payloads::identify_payload, rest_client::data::MessageData},
+    data::{
+        state::State,
+        user::{CustomStatus, Presence},
+    },
 };
 
 use self::{
-    data::{DispatchReady, GatewayMessage},
-    payloads::{heartbeat_payload, resume_payload},
+    data::{
+        DispatchGuildCreate, DispatchGuildDelete, DispatchGuildUpdate, DispatchMessageAck,
+        DispatchMessageDelete, DispatchMessageDeleteBulk, DispatchMessageReaction,
+        DispatchMessageReactionRemoveAll, DispatchMessageReactionRemoveEmoji,
+        DispatchMessageUpdate, DispatchReady, DispatchRelationshipAdd, DispatchRelationshipRemove,
+        DispatchSessionsReplace, DispatchTypingStart, GatewayMessage, PresenceData,
+    },
+    payloads::{heartbeat_payload, presence_update_payload, resume_payload},
 };
 
 const URL: &str = "wss://gateway.discord.gg";
@@ -59,7 +68,23 @@ pub enum GatewayState {
 #[derive(Debug, Clone)]
 pub enum GatewayEvent {
     ReconnectNeeded,
-    Message(DispatchMessage),
+    MessageCreated(MessageData),
+    MessageUpdated(DispatchMessageUpdate),
+    MessageDeleted(DispatchMessageDelete),
+    MessagesDeleted(DispatchMessageDeleteBulk),
+    MessageAcked(DispatchMessageAck),
+    ReactionAdded(DispatchMessageReaction),
+    ReactionRemoved(DispatchMessageReaction),
+    ReactionsRemoved(DispatchMessageReactionRemoveAll),
+    EmojiReactionsRemoved(DispatchMessageReactionRemoveEmoji),
+    GuildCreated(DispatchGuildCreate),
+    GuildUpdated(DispatchGuildUpdate),
+    GuildDeleted(DispatchGuildDelete),
+    RelationshipAdded(DispatchRelationshipAdd),
+    RelationshipRemoved(DispatchRelationshipRemove),
+    PresenceUpdated(PresenceData),
+    SessionsReplaced(DispatchSessionsReplace),
+    TypingStarted(DispatchTypingStart),
 }
 
 #[derive(Debug, Clone)]
@@ -186,6 +211,16 @@ impl Gateway {
         });
     }
 
+    /// Changes the presence and custom status of all sessions of the current user
+    pub async fn update_presence(
+        self,
+        presence: Presence,
+        custom_status: Option<CustomStatus>,
+    ) -> Result<()> {
+        self.send(presence_update_payload(presence, custom_status.as_ref()))
+            .await
+    }
+
     pub fn subscribe(&self) -> Subscription<GatewayEvent> {
         subscription::unfold(self.inner.token.clone(), self.clone(), |this| async move {
             let event = this.inner.event_receiver.lock().await.recv().await;
@@ -352,11 +387,123 @@ impl Gateway {
                 info!("Session successfully resumed");
                 self.set_state(GatewayState::Open).await;
             }
-            "MESSAGE_CREATE" | "MESSAGE_UPDATE" => {
-                let data = serde_json::from_value::<DispatchMessage>(data)?;
+            "MESSAGE_CREATE" => {
+                let data = serde_json::from_value::<MessageData>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::MessageCreated(data))
+                    .await?;
+            }
+            "MESSAGE_ACK" => {
+                let data = serde_json::from_value::<DispatchMessageAck>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::MessageAcked(data))
+                    .await?;
+            }
+            "MESSAGE_UPDATE" => {
+                let data = serde_json::from_value::<DispatchMessageUpdate>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::MessageUpdated(data))
+                    .await?;
+            }
+            "MESSAGE_DELETE" => {
+                let data = serde_json::from_value::<DispatchMessageDelete>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::MessageDeleted(data))
+                    .await?;
+            }
+            "MESSAGE_DELETE_BULK" => {
+                let data = serde_json::from_value::<DispatchMessageDeleteBulk>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::MessagesDeleted(data))
+                    .await?;
+            }
+            "MESSAGE_REACTION_ADD" => {
+                let data = serde_json::from_value::<DispatchMessageReaction>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::ReactionAdded(data))
+                    .await?;
+            }
+            "MESSAGE_REACTION_REMOVE" => {
+                let data = serde_json::from_value::<DispatchMessageReaction>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::ReactionRemoved(data))
+                    .await?;
+            }
+            "MESSAGE_REACTION_REMOVE_ALL" => {
+                let data = serde_json::from_value::<DispatchMessageReactionRemoveAll>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::ReactionsRemoved(data))
+                    .await?;
+            }
+            "MESSAGE_REACTION_REMOVE_EMOJI" => {
+                let data = serde_json::from_value::<DispatchMessageReactionRemoveEmoji>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::EmojiReactionsRemoved(data))
+                    .await?;
+            }
+            "GUILD_CREATE" => {
+                let data = serde_json::from_value::<DispatchGuildCreate>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::GuildCreated(data))
+                    .await?;
+            }
+            "GUILD_UPDATE" => {
+                let data = serde_json::from_value::<DispatchGuildUpdate>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::GuildUpdated(data))
+                    .await?;
+            }
+            "GUILD_DELETE" => {
+                let data = serde_json::from_value::<DispatchGuildDelete>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::GuildDeleted(data))
+                    .await?;
+            }
+            "RELATIONSHIP_ADD" => {
+                let data = serde_json::from_value::<DispatchRelationshipAdd>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::RelationshipAdded(data))
+                    .await?;
+            }
+            "RELATIONSHIP_REMOVE" => {
+                let data = serde_json::from_value::<DispatchRelationshipRemove>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::RelationshipRemoved(data))
+                    .await?;
+            }
+            "PRESENCE_UPDATE" => {
+                let data = serde_json::from_value::<PresenceData>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::PresenceUpdated(data))
+                    .await?;
+            }
+            "SESSIONS_REPLACE" => {
+                let data = serde_json::from_value::<DispatchSessionsReplace>(data)?;
+                self.inner
+                    .event_sender
+                    .send(GatewayEvent::SessionsReplaced(data))
+                    .await?;
+            }
+            "TYPING_START" => {
+                let data = serde_json::from_value::<DispatchTypingStart>(data)?;
                 self.inner
                     .event_sender
-                    .send(GatewayEvent::Message(data))
+                    .send(GatewayEvent::TypingStarted(data))
                     .await?;
             }
             msg_type => {
@@ -1,5 +1,7 @@
 use serde_json::json;
 
+use crate::data::user::{CustomStatus, Presence};
+
 pub fn heartbeat_payload(sequence: Option<u32>) -> String {
     if let Some(s) = sequence {
         json!({"op": 1, "d": s})
@@ -9,13 +11,17 @@ pub fn heartbeat_payload(sequence: Option<u32>) -> String {
     .to_string()
 }
 
+/// Capabilities of the client. USER_SETTINGS_PROTO (1 << 9) is not included,
+/// otherwise the settings are not sent as JSON in READY
+const CAPABILITIES: u32 = 509;
+
 pub fn identify_payload(token: &str) -> String {
     json!({
         "op": 2,
         "d": {
             "token": token,
             "properties": {
-                "capabilities": 1021,
+                "capabilities": CAPABILITIES,
                 "os": "Windows",
                 "browser": "Chrome",
                 "device": "strife"
@@ -36,3 +42,27 @@ pub fn resume_payload(token: &str, session_id: &str, sequence: u32) -> String {
     })
     .to_string()
 }
+
+pub fn presence_update_payload(presence: Presence, custom_status: Option<&CustomStatus>) -> String {
+    // The custom status is sent as an activity, its expiry is only stored in the user settings
+    let activities = custom_status
+        .map(|status| {
+            vec![json!({
+                "type": 4,
+                "name": "Custom Status",
+                "state": status.text
+            })]
+        })
+        .unwrap_or_default();
+
+    json!({
+        "op": 3,
+        "d": {
+            "status": presence,
+            "since": 0,
+            "activities": activities,
+            "afk": false
+        }
+    })
+    .to_string()
+}
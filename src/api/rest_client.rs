@@ -1 +0,0 @@
-pub const REST_BASE_URL: &str = "https://discord.com/api/v9";
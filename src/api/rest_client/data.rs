@@ -0,0 +1,148 @@
+use serde::Deserialize;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::data::{
+    guild::Member,
+    message::{
+        Attachment, Embed, Message, MessageFlags, MessageKind, MessageReference, MessageStatus,
+        Reaction,
+    },
+    snowflake::Snowflake,
+    state::{PrivateChannel, PrivateChannelKind},
+    user::User,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelData {
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub kind: u16,
+    pub guild_id: Option<Snowflake>,
+    pub position: Option<i32>,
+    pub name: Option<String>,
+    pub topic: Option<String>,
+    pub parent_id: Option<Snowflake>,
+    pub last_message_id: Option<Snowflake>,
+    #[serde(default)]
+    pub recipients: Vec<User>,
+    pub icon: Option<String>,
+    pub owner_id: Option<Snowflake>,
+}
+
+impl Into<PrivateChannel> for ChannelData {
+    fn into(self) -> PrivateChannel {
+        PrivateChannel {
+            id: self.id,
+            kind: match self.kind {
+                3 => PrivateChannelKind::Group,
+                _ => PrivateChannelKind::DirectMessage,
+            },
+            recipients: self.recipients.into_iter().map(|r| r.id).collect(),
+            owner_id: self.owner_id,
+            name: self.name,
+            icon: self.icon,
+            icon_handle: None,
+            last_message_id: self.last_message_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageData {
+    pub id: Snowflake,
+    pub channel_id: Snowflake,
+    /// Only sent with messages of guild channels from the gateway
+    pub guild_id: Option<Snowflake>,
+    /// Guild member of the author, only sent with `guild_id`
+    pub member: Option<Member>,
+    #[serde(rename = "type")]
+    pub kind: u16,
+    pub author: User,
+    pub content: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub edited_timestamp: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub embeds: Vec<Embed>,
+    #[serde(default)]
+    pub mentions: Vec<User>,
+    #[serde(default)]
+    pub mention_roles: Vec<Snowflake>,
+    #[serde(default)]
+    pub mention_everyone: bool,
+    pub message_reference: Option<MessageReference>,
+    pub referenced_message: Option<Box<MessageData>>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub flags: MessageFlags,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+    pub nonce: Option<Value>,
+}
+
+impl MessageData {
+    /// Nonces can be strings or integers
+    pub fn nonce(&self) -> Option<String> {
+        match &self.nonce {
+            Some(Value::String(nonce)) => Some(nonce.clone()),
+            Some(nonce) => Some(nonce.to_string()),
+            None => None,
+        }
+    }
+
+    /// All users that are included in the message
+    pub fn users(&self) -> impl Iterator<Item = &User> {
+        std::iter::once(&self.author)
+            .chain(self.mentions.iter())
+            .chain(self.referenced_message.iter().map(|m| &m.author))
+    }
+}
+
+impl Into<Message> for MessageData {
+    fn into(self) -> Message {
+        Message {
+            nonce: self.nonce(),
+            id: self.id,
+            kind: MessageKind::from(self.kind),
+            author_id: self.author.id,
+            content: self.content,
+            timestamp: self.timestamp,
+            edited_timestamp: self.edited_timestamp,
+            attachments: self.attachments,
+            embeds: self.embeds,
+            mentions: self.mentions.into_iter().map(|u| u.id).collect(),
+            mention_roles: self.mention_roles,
+            mention_everyone: self.mention_everyone,
+            reference: self.message_reference,
+            referenced_message: self.referenced_message.map(|m| Box::new((*m).into())),
+            pinned: self.pinned,
+            flags: self.flags,
+            reactions: self.reactions,
+            status: MessageStatus::Sent,
+            deleted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RelationshipData {
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub kind: u16,
+    pub user: User,
+    pub nickname: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildData {
+    pub id: Snowflake,
+    pub name: String,
+    pub icon: Option<String>,
+    pub owner: Option<bool>,
+    pub permissions: Option<String>,
+}
@@ -0,0 +1,329 @@
+pub mod data;
+mod payloads;
+mod ratelimit;
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use reqwest::{header::CONTENT_TYPE, Method, RequestBuilder, Response, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::data::{
+    message::ReactionEmoji,
+    snowflake::Snowflake,
+    user::{CustomStatus, Presence, User},
+};
+
+use self::{
+    data::{ChannelData, GuildData, MessageData, RelationshipData},
+    payloads::{
+        CreateMessagePayload, CreatePrivateChannelPayload, CustomStatusPayload, EditMessagePayload,
+        FriendRequestPayload, MessagesQuery, PresenceSettingsPayload, RelationshipPayload,
+    },
+    ratelimit::{RateLimiter, Route},
+};
+
+const BASE_URL: &str = "https://discord.com/api/v9";
+const MAX_RETRIES: u32 = 5;
+
+#[derive(Clone)]
+pub struct RestClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RestClient {
+    pub fn new(token: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: String::from(BASE_URL),
+            token,
+            rate_limiter: Default::default(),
+        }
+    }
+
+    /// Creates a client for another token that shares the connection pool of this client.
+    /// Rate limits are tracked per token, so the new client starts with empty buckets
+    pub fn with_token(&self, token: String) -> Self {
+        Self {
+            client: self.client.clone(),
+            base_url: self.base_url.clone(),
+            token,
+            rate_limiter: Default::default(),
+        }
+    }
+
+    /// Sends all requests to another server instead of the discord api (e.g. a local test server)
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    pub async fn current_user(self) -> Result<User> {
+        self.send(self.request(Method::GET, "/users/@me")).await
+    }
+
+    pub async fn user(self, user_id: Snowflake) -> Result<User> {
+        self.send(self.request(Method::GET, &format!("/users/{user_id}")))
+            .await
+    }
+
+    pub async fn private_channels(self) -> Result<Vec<ChannelData>> {
+        self.send(self.request(Method::GET, "/users/@me/channels"))
+            .await
+    }
+
+    /// Opens the direct message channel with a user. Returns the existing channel if there is one
+    pub async fn create_private_channel(self, recipient_id: Snowflake) -> Result<ChannelData> {
+        let request = self.json(
+            self.request(Method::POST, "/users/@me/channels"),
+            &CreatePrivateChannelPayload {
+                recipients: vec![recipient_id],
+            },
+        )?;
+
+        self.send(request).await
+    }
+
+    pub async fn channel(self, channel_id: Snowflake) -> Result<ChannelData> {
+        self.send(self.request(Method::GET, &format!("/channels/{channel_id}")))
+            .await
+    }
+
+    pub async fn messages(
+        self,
+        channel_id: Snowflake,
+        limit: u8,
+        before: Option<Snowflake>,
+    ) -> Result<Vec<MessageData>> {
+        let request = self
+            .request(Method::GET, &format!("/channels/{channel_id}/messages"))
+            .query(&MessagesQuery {
+                limit: Some(limit),
+                before,
+                after: None,
+            });
+
+        self.send(request).await
+    }
+
+    pub async fn create_message(
+        self,
+        channel_id: Snowflake,
+        content: String,
+        nonce: String,
+    ) -> Result<MessageData> {
+        let request = self.json(
+            self.request(Method::POST, &format!("/channels/{channel_id}/messages")),
+            &CreateMessagePayload { content, nonce },
+        )?;
+
+        self.send(request).await
+    }
+
+    pub async fn edit_message(
+        self,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        content: String,
+    ) -> Result<MessageData> {
+        let request = self.json(
+            self.request(
+                Method::PATCH,
+                &format!("/channels/{channel_id}/messages/{message_id}"),
+            ),
+            &EditMessagePayload { content },
+        )?;
+
+        self.send(request).await
+    }
+
+    pub async fn delete_message(self, channel_id: Snowflake, message_id: Snowflake) -> Result<()> {
+        self.send_empty(self.request(
+            Method::DELETE,
+            &format!("/channels/{channel_id}/messages/{message_id}"),
+        ))
+        .await
+    }
+
+    pub async fn add_reaction(
+        self,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        emoji: ReactionEmoji,
+    ) -> Result<()> {
+        let emoji = emoji.url_name();
+        self.send_empty(self.request(
+            Method::PUT,
+            &format!("/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/@me"),
+        ))
+        .await
+    }
+
+    pub async fn remove_own_reaction(
+        self,
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        emoji: ReactionEmoji,
+    ) -> Result<()> {
+        let emoji = emoji.url_name();
+        self.send_empty(self.request(
+            Method::DELETE,
+            &format!("/channels/{channel_id}/messages/{message_id}/reactions/{emoji}/@me"),
+        ))
+        .await
+    }
+
+    /// Shows the current user as typing in a channel for 10 seconds or until it sends a message
+    pub async fn trigger_typing(self, channel_id: Snowflake) -> Result<()> {
+        self.send_empty(self.request(Method::POST, &format!("/channels/{channel_id}/typing")))
+            .await
+    }
+
+    pub async fn relationships(self) -> Result<Vec<RelationshipData>> {
+        self.send(self.request(Method::GET, "/users/@me/relationships"))
+            .await
+    }
+
+    pub async fn send_friend_request(
+        self,
+        username: String,
+        discriminator: Option<u16>,
+    ) -> Result<()> {
+        let request = self.json(
+            self.request(Method::POST, "/users/@me/relationships"),
+            &FriendRequestPayload {
+                username,
+                discriminator,
+            },
+        )?;
+
+        self.send_empty(request).await
+    }
+
+    /// Accepts the friend request of a user
+    pub async fn accept_friend_request(self, user_id: Snowflake) -> Result<()> {
+        let request = self.json(
+            self.request(Method::PUT, &format!("/users/@me/relationships/{user_id}")),
+            &RelationshipPayload { kind: None },
+        )?;
+
+        self.send_empty(request).await
+    }
+
+    pub async fn block_user(self, user_id: Snowflake) -> Result<()> {
+        let request = self.json(
+            self.request(Method::PUT, &format!("/users/@me/relationships/{user_id}")),
+            &RelationshipPayload { kind: Some(2) },
+        )?;
+
+        self.send_empty(request).await
+    }
+
+    /// Removes a friend, ignores or cancels a friend request or unblocks a user
+    pub async fn remove_relationship(self, user_id: Snowflake) -> Result<()> {
+        self.send_empty(self.request(
+            Method::DELETE,
+            &format!("/users/@me/relationships/{user_id}"),
+        ))
+        .await
+    }
+
+    /// Saves the presence and custom status in the user settings, so they are restored by the next session
+    pub async fn update_presence_settings(
+        self,
+        presence: Presence,
+        custom_status: Option<CustomStatus>,
+    ) -> Result<()> {
+        let request = self.json(
+            self.request(Method::PATCH, "/users/@me/settings"),
+            &PresenceSettingsPayload {
+                status: presence,
+                custom_status: custom_status.map(|status| CustomStatusPayload {
+                    text: status.text,
+                    expires_at: status.expires_at,
+                }),
+            },
+        )?;
+
+        self.send_empty(request).await
+    }
+
+    pub async fn guilds(self) -> Result<Vec<GuildData>> {
+        self.send(self.request(Method::GET, "/users/@me/guilds"))
+            .await
+    }
+
+    pub async fn guild(self, guild_id: Snowflake) -> Result<GuildData> {
+        self.send(self.request(Method::GET, &format!("/guilds/{guild_id}")))
+            .await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.base_url))
+            .header("Authorization", &self.token)
+    }
+
+    fn json<T: Serialize>(&self, request: RequestBuilder, body: &T) -> Result<RequestBuilder> {
+        Ok(request
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(body)?))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        let data = self.execute(request).await?.bytes().await?;
+
+        Ok(serde_json::from_slice::<T>(&data)?)
+    }
+
+    /// Sends a request whose response has no content
+    async fn send_empty(&self, request: RequestBuilder) -> Result<()> {
+        self.execute(request).await?;
+
+        Ok(())
+    }
+
+    /// Executes a request while respecting the rate limits of its route.
+    /// Requests that are rate limited anyway are retried after the time discord asks for
+    async fn execute(&self, request: RequestBuilder) -> Result<Response> {
+        let request = request.build()?;
+        let route = Route::new(request.method(), request.url().path());
+
+        for _ in 0..MAX_RETRIES {
+            let mut ticket = self.rate_limiter.acquire(&route).await;
+
+            let response = self
+                .client
+                .execute(
+                    request
+                        .try_clone()
+                        .ok_or(anyhow!("Failed to clone request"))?,
+                )
+                .await?;
+
+            self.rate_limiter
+                .update(&route, &mut ticket, response.headers())
+                .await;
+
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                self.rate_limiter
+                    .limited(&route, &mut ticket, response.headers())
+                    .await;
+                continue;
+            }
+
+            return Ok(response.error_for_status()?);
+        }
+
+        Err(anyhow!("Request was rate limited {MAX_RETRIES} times"))
+    }
+}
+
+impl Default for RestClient {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
@@ -0,0 +1,57 @@
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::data::{snowflake::Snowflake, user::Presence};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessagesQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Snowflake>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Snowflake>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateMessagePayload {
+    pub content: String,
+    pub nonce: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EditMessagePayload {
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePrivateChannelPayload {
+    pub recipients: Vec<Snowflake>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelationshipPayload {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FriendRequestPayload {
+    pub username: String,
+    /// Users that migrated to unique usernames do not have a discriminator
+    pub discriminator: Option<u16>,
+}
+
+/// Presence related user settings. A custom status of `None` clears it
+#[derive(Debug, Clone, Serialize)]
+pub struct PresenceSettingsPayload {
+    pub status: Presence,
+    pub custom_status: Option<CustomStatusPayload>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomStatusPayload {
+    pub text: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
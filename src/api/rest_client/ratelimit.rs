@@ -0,0 +1,415 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+
+use reqwest::{header::HeaderMap, Method};
+use tokio::{
+    sync::{Mutex, OwnedMutexGuard},
+    time::{self, Instant},
+};
+use tracing::warn;
+
+/// Path segments that are followed by a major parameter
+const MAJOR_PARAMETERS: [&str; 3] = ["channels", "guilds", "webhooks"];
+
+/// Identifies a rate limited route by its method, its path with minor parameters removed and its major parameter
+#[derive(Debug, Clone)]
+pub struct Route {
+    key: String,
+    major: String,
+}
+
+impl Route {
+    pub fn new(method: &Method, path: &str) -> Self {
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        let mut key = format!("{method} ");
+        let mut major = String::new();
+
+        for (i, segment) in segments.iter().enumerate() {
+            let previous = if i > 0 { segments[i - 1] } else { "" };
+            let is_id = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+
+            key.push('/');
+            if is_id && major.is_empty() && MAJOR_PARAMETERS.contains(&previous) {
+                major = segment.to_string();
+                key.push_str(segment);
+            } else if is_id || previous == "reactions" {
+                key.push_str(":id");
+            } else {
+                key.push_str(segment);
+            }
+        }
+
+        Self { key, major }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Bucket {
+    remaining: Option<u32>,
+    reset: Option<Instant>,
+}
+
+/// Exclusive access to a bucket for the duration of one request. Other requests for the same bucket
+/// are queued until the ticket is dropped
+pub struct Ticket {
+    bucket: Arc<Mutex<Bucket>>,
+    state: OwnedMutexGuard<Bucket>,
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// Bucket hashes reported by discord for each route
+    hashes: Mutex<HashMap<String, String>>,
+    buckets: Mutex<HashMap<String, Arc<Mutex<Bucket>>>>,
+    global_reset: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Waits until a request on the route would not exceed the global limit or the limit of its bucket
+    pub async fn acquire(&self, route: &Route) -> Ticket {
+        let global_reset = *self.global_reset.lock().await;
+        if let Some(reset) = global_reset {
+            time::sleep_until(reset).await;
+        }
+
+        let bucket = self.bucket(route).await;
+        let mut state = bucket.clone().lock_owned().await;
+
+        if let (Some(0), Some(reset)) = (state.remaining, state.reset) {
+            time::sleep_until(reset).await;
+            state.remaining = None;
+            state.reset = None;
+        }
+
+        Ticket { bucket, state }
+    }
+
+    /// Updates the bucket of the route with the rate limit headers of a response
+    pub async fn update(&self, route: &Route, ticket: &mut Ticket, headers: &HeaderMap) {
+        if let Some(hash) = header::<String>(headers, "x-ratelimit-bucket") {
+            // Routes sharing a hash share a bucket, so the current bucket is registered under the hash
+            // unless another route registered its bucket first
+            let bucket = self
+                .buckets
+                .lock()
+                .await
+                .entry(format!("{hash}:{}", route.major))
+                .or_insert_with(|| ticket.bucket.clone())
+                .clone();
+            self.hashes.lock().await.insert(route.key.clone(), hash);
+
+            // The route uses the shared bucket from now on, so it gets the limits of this response
+            if !Arc::ptr_eq(&bucket, &ticket.bucket) {
+                ticket.state = bucket.clone().lock_owned().await;
+                ticket.bucket = bucket;
+            }
+        }
+
+        if let Some(remaining) = header::<u32>(headers, "x-ratelimit-remaining") {
+            ticket.state.remaining = Some(remaining);
+        }
+
+        if let Some(reset_after) = header::<f64>(headers, "x-ratelimit-reset-after") {
+            ticket.state.reset = Some(Instant::now() + Duration::from_secs_f64(reset_after));
+        }
+    }
+
+    /// Applies the limit of a 429 response so that the next `acquire` waits until the request can be retried
+    pub async fn limited(&self, route: &Route, ticket: &mut Ticket, headers: &HeaderMap) {
+        let retry_after =
+            Duration::from_secs_f64(header::<f64>(headers, "retry-after").unwrap_or(1.0));
+        let reset = Instant::now() + retry_after;
+
+        let global = header::<bool>(headers, "x-ratelimit-global").unwrap_or(false)
+            || header::<String>(headers, "x-ratelimit-scope").as_deref() == Some("global");
+
+        if global {
+            warn!("Hit global rate limit, retrying in {retry_after:?}");
+            *self.global_reset.lock().await = Some(reset);
+        } else {
+            warn!(
+                "Hit rate limit on {}, retrying in {retry_after:?}",
+                route.key
+            );
+            ticket.state.remaining = Some(0);
+            ticket.state.reset = Some(reset);
+        }
+    }
+
+    async fn bucket(&self, route: &Route) -> Arc<Mutex<Bucket>> {
+        let key = if let Some(hash) = self.hashes.lock().await.get(&route.key) {
+            format!("{hash}:{}", route.major)
+        } else {
+            route.key.clone()
+        };
+
+        self.buckets.lock().await.entry(key).or_default().clone()
+    }
+}
+
+fn header<T: FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::Mutex,
+        time::Instant,
+    };
+
+    use crate::{
+        api::rest_client::{RestClient, MAX_RETRIES},
+        data::snowflake::Snowflake,
+    };
+
+    /// Rate limits are only checked to be waited for, a little less time is allowed for timer precision
+    const RESET: Duration = Duration::from_millis(300);
+    const TOLERANCE: Duration = Duration::from_millis(10);
+
+    struct Scripted {
+        status: u16,
+        headers: Vec<(&'static str, &'static str)>,
+    }
+
+    fn ok(headers: Vec<(&'static str, &'static str)>) -> Scripted {
+        Scripted {
+            status: 200,
+            headers,
+        }
+    }
+
+    fn too_many_requests(headers: Vec<(&'static str, &'static str)>) -> Scripted {
+        Scripted {
+            status: 429,
+            headers,
+        }
+    }
+
+    /// Local stand-in for the discord api that answers requests with scripted responses in order.
+    /// Once the script is done every request succeeds without rate limit headers
+    struct StandIn {
+        url: String,
+        /// Path and arrival of every request
+        requests: Arc<Mutex<Vec<(String, Instant)>>>,
+    }
+
+    impl StandIn {
+        async fn start(script: Vec<Scripted>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let script = Arc::new(Mutex::new(VecDeque::from(script)));
+            let requests = Arc::new(Mutex::new(vec![]));
+
+            let received = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, script.clone(), received.clone()));
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        fn client(&self) -> RestClient {
+            RestClient::new(String::from("token")).with_base_url(self.url.clone())
+        }
+
+        async fn requests(&self) -> Vec<(String, Instant)> {
+            self.requests.lock().await.clone()
+        }
+    }
+
+    /// Answers the requests of one connection, which is kept alive by the client
+    async fn serve(
+        mut stream: TcpStream,
+        script: Arc<Mutex<VecDeque<Scripted>>>,
+        requests: Arc<Mutex<Vec<(String, Instant)>>>,
+    ) {
+        let mut buffer = Vec::new();
+
+        loop {
+            let head_end = loop {
+                if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break end + 4;
+                }
+                let mut chunk = [0; 1024];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                }
+            };
+
+            let head = String::from_utf8_lossy(&buffer[..head_end]).to_lowercase();
+            let path = head.split(' ').nth(1).unwrap_or_default().to_string();
+            let body_len = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|len| len.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+
+            while buffer.len() < head_end + body_len {
+                let mut chunk = [0; 1024];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                }
+            }
+            buffer.drain(..head_end + body_len);
+
+            requests.lock().await.push((path, Instant::now()));
+            let response = script.lock().await.pop_front().unwrap_or(ok(vec![]));
+
+            let mut reply = format!(
+                "HTTP/1.1 {} Scripted\r\ncontent-length: 0\r\n",
+                response.status
+            );
+            for (name, value) in response.headers {
+                reply.push_str(&format!("{name}: {value}\r\n"));
+            }
+            reply.push_str("\r\n");
+
+            if stream.write_all(reply.as_bytes()).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_for_reset_when_no_requests_remain() {
+        let stand_in = StandIn::start(vec![ok(vec![
+            ("X-RateLimit-Bucket", "typing"),
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset-After", "0.3"),
+        ])])
+        .await;
+        let client = stand_in.client();
+
+        client
+            .clone()
+            .trigger_typing(Snowflake::new(1))
+            .await
+            .unwrap();
+        client.trigger_typing(Snowflake::new(1)).await.unwrap();
+
+        let requests = stand_in.requests().await;
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].1 - requests[0].1 >= RESET - TOLERANCE);
+    }
+
+    #[tokio::test]
+    async fn does_not_wait_while_requests_remain() {
+        let stand_in = StandIn::start(vec![ok(vec![
+            ("X-RateLimit-Bucket", "typing"),
+            ("X-RateLimit-Remaining", "4"),
+            ("X-RateLimit-Reset-After", "5"),
+        ])])
+        .await;
+        let client = stand_in.client();
+
+        let start = Instant::now();
+        client
+            .clone()
+            .trigger_typing(Snowflake::new(1))
+            .await
+            .unwrap();
+        client.trigger_typing(Snowflake::new(1)).await.unwrap();
+
+        assert!(start.elapsed() < RESET / 2);
+    }
+
+    #[tokio::test]
+    async fn routes_with_the_same_hash_share_limits() {
+        let stand_in = StandIn::start(vec![
+            ok(vec![
+                ("X-RateLimit-Bucket", "messages"),
+                ("X-RateLimit-Remaining", "4"),
+                ("X-RateLimit-Reset-After", "5"),
+            ]),
+            ok(vec![
+                ("X-RateLimit-Bucket", "messages"),
+                ("X-RateLimit-Remaining", "0"),
+                ("X-RateLimit-Reset-After", "0.3"),
+            ]),
+        ])
+        .await;
+        let client = stand_in.client();
+
+        client
+            .clone()
+            .trigger_typing(Snowflake::new(1))
+            .await
+            .unwrap();
+        // The limits of the second route are applied to the bucket registered by the first one
+        client
+            .clone()
+            .delete_message(Snowflake::new(1), Snowflake::new(2))
+            .await
+            .unwrap();
+        client.trigger_typing(Snowflake::new(1)).await.unwrap();
+
+        let requests = stand_in.requests().await;
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].1 - requests[1].1 >= RESET - TOLERANCE);
+    }
+
+    #[tokio::test]
+    async fn retries_after_retry_after() {
+        let stand_in = StandIn::start(vec![too_many_requests(vec![
+            ("X-RateLimit-Bucket", "typing"),
+            ("Retry-After", "0.3"),
+        ])])
+        .await;
+
+        stand_in
+            .client()
+            .trigger_typing(Snowflake::new(1))
+            .await
+            .unwrap();
+
+        let requests = stand_in.requests().await;
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].1 - requests[0].1 >= RESET - TOLERANCE);
+    }
+
+    #[tokio::test]
+    async fn global_limit_blocks_other_routes() {
+        let stand_in = StandIn::start(vec![too_many_requests(vec![
+            ("Retry-After", "0.3"),
+            ("X-RateLimit-Global", "true"),
+        ])])
+        .await;
+        let client = stand_in.client();
+
+        let limited = tokio::spawn(client.clone().trigger_typing(Snowflake::new(1)));
+        // The other route is requested after the global limit was hit
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        client.trigger_typing(Snowflake::new(2)).await.unwrap();
+        limited.await.unwrap().unwrap();
+
+        let requests = stand_in.requests().await;
+        assert_eq!(requests.len(), 3);
+        let other = requests
+            .iter()
+            .find(|(path, _)| path.contains("/channels/2/"))
+            .unwrap();
+        assert!(other.1 - requests[0].1 >= RESET - TOLERANCE);
+    }
+
+    #[tokio::test]
+    async fn stops_retrying_after_max_retries() {
+        let script = (0..MAX_RETRIES + 1)
+            .map(|_| too_many_requests(vec![("Retry-After", "0")]))
+            .collect();
+        let stand_in = StandIn::start(script).await;
+
+        let result = stand_in.client().trigger_typing(Snowflake::new(1)).await;
+
+        assert!(result.is_err());
+        assert_eq!(stand_in.requests().await.len(), MAX_RETRIES as usize);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use iced_native::image;
+use serde::{de, Deserialize, Deserializer};
+
+use super::snowflake::Snowflake;
+
+fn str_to_u64<'a, D: Deserializer<'a>>(deserializer: D) -> Result<u64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Guild {
+    pub id: Snowflake,
+    #[serde(default)]
+    pub name: String,
+    pub icon: Option<String>,
+    #[serde(skip)]
+    pub icon_handle: Option<image::Handle>,
+    pub owner_id: Option<Snowflake>,
+    #[serde(default)]
+    pub channels: Vec<GuildChannel>,
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    #[serde(default)]
+    pub emojis: Vec<Emoji>,
+    /// The guild member of the current user
+    #[serde(skip)]
+    pub member: Option<Member>,
+    /// Members that were sent by discord, by user id
+    #[serde(skip)]
+    pub members: HashMap<Snowflake, Member>,
+    /// Unavailable guilds only contain an id until they are sent again with GUILD_CREATE
+    #[serde(default)]
+    pub unavailable: bool,
+}
+
+impl PartialEq for Guild {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "u16")]
+pub enum GuildChannelKind {
+    Text,
+    Voice,
+    Category,
+    Announcement,
+    AnnouncementThread,
+    PublicThread,
+    PrivateThread,
+    Stage,
+    Directory,
+    Forum,
+    Unknown(u16),
+}
+
+impl From<u16> for GuildChannelKind {
+    fn from(kind: u16) -> Self {
+        match kind {
+            0 => GuildChannelKind::Text,
+            2 => GuildChannelKind::Voice,
+            4 => GuildChannelKind::Category,
+            5 => GuildChannelKind::Announcement,
+            10 => GuildChannelKind::AnnouncementThread,
+            11 => GuildChannelKind::PublicThread,
+            12 => GuildChannelKind::PrivateThread,
+            13 => GuildChannelKind::Stage,
+            14 => GuildChannelKind::Directory,
+            15 => GuildChannelKind::Forum,
+            kind => GuildChannelKind::Unknown(kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GuildChannel {
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub kind: GuildChannelKind,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub position: i32,
+    /// The category of the channel
+    pub parent_id: Option<Snowflake>,
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub nsfw: bool,
+    #[serde(default)]
+    pub permission_overwrites: Vec<PermissionOverwrite>,
+    pub last_message_id: Option<Snowflake>,
+}
+
+impl PartialEq for GuildChannel {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "u8")]
+pub enum OverwriteKind {
+    Role,
+    Member,
+}
+
+impl From<u8> for OverwriteKind {
+    fn from(kind: u8) -> Self {
+        match kind {
+            1 => OverwriteKind::Member,
+            _ => OverwriteKind::Role,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PermissionOverwrite {
+    /// Role or user id
+    pub id: Snowflake,
+    #[serde(rename = "type")]
+    pub kind: OverwriteKind,
+    #[serde(deserialize_with = "str_to_u64")]
+    pub allow: u64,
+    #[serde(deserialize_with = "str_to_u64")]
+    pub deny: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Role {
+    pub id: Snowflake,
+    pub name: String,
+    #[serde(default)]
+    pub color: u32,
+    #[serde(default)]
+    pub position: i32,
+    #[serde(deserialize_with = "str_to_u64")]
+    pub permissions: u64,
+    #[serde(default)]
+    pub hoist: bool,
+    #[serde(default)]
+    pub mentionable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Emoji {
+    pub id: Option<Snowflake>,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub animated: bool,
+    #[serde(default = "default_available")]
+    pub available: bool,
+}
+
+fn default_available() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub nick: Option<String>,
+    #[serde(default)]
+    pub roles: Vec<Snowflake>,
+}
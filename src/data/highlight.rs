@@ -0,0 +1,625 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Comment,
+    /// Error log level
+    Error,
+    /// Warning log level
+    Warning,
+}
+
+/// Line of highlighted code
+pub type Line = Vec<(TokenKind, String)>;
+
+struct Language {
+    keywords: &'static [&'static str],
+    types: &'static [&'static str],
+    /// Identifiers starting with an uppercase letter are types
+    capitalized_types: bool,
+    /// Keywords are matched case insensitively
+    ignore_case: bool,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Block comments can contain other block comments
+    nested_comments: bool,
+    quotes: &'static [char],
+    /// Error and warning log levels are highlighted
+    log_levels: bool,
+}
+
+const RUST: Language = Language {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    types: &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ],
+    capitalized_types: true,
+    ignore_case: false,
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_comments: true,
+    quotes: &['"'],
+    log_levels: false,
+};
+
+const SQL: Language = Language {
+    keywords: &[
+        "add",
+        "all",
+        "alter",
+        "and",
+        "as",
+        "asc",
+        "begin",
+        "between",
+        "by",
+        "case",
+        "commit",
+        "create",
+        "cross",
+        "default",
+        "delete",
+        "desc",
+        "distinct",
+        "drop",
+        "else",
+        "end",
+        "exists",
+        "false",
+        "foreign",
+        "from",
+        "full",
+        "group",
+        "having",
+        "if",
+        "in",
+        "index",
+        "inner",
+        "insert",
+        "into",
+        "is",
+        "join",
+        "key",
+        "left",
+        "like",
+        "limit",
+        "not",
+        "null",
+        "offset",
+        "on",
+        "or",
+        "order",
+        "outer",
+        "primary",
+        "references",
+        "returning",
+        "right",
+        "rollback",
+        "select",
+        "set",
+        "table",
+        "then",
+        "true",
+        "union",
+        "unique",
+        "update",
+        "using",
+        "values",
+        "view",
+        "when",
+        "where",
+        "with",
+    ],
+    types: &[
+        "bigint",
+        "blob",
+        "boolean",
+        "char",
+        "date",
+        "decimal",
+        "float",
+        "int",
+        "integer",
+        "json",
+        "jsonb",
+        "numeric",
+        "real",
+        "serial",
+        "smallint",
+        "text",
+        "time",
+        "timestamp",
+        "timestamptz",
+        "uuid",
+        "varchar",
+    ],
+    capitalized_types: false,
+    ignore_case: true,
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    nested_comments: false,
+    quotes: &['\'', '"'],
+    log_levels: false,
+};
+
+/// Also used for other languages with a syntax like C
+const JAVASCRIPT: Language = Language {
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    types: &[
+        "any",
+        "boolean",
+        "char",
+        "double",
+        "float",
+        "int",
+        "long",
+        "number",
+        "short",
+        "string",
+        "undefined",
+        "unknown",
+    ],
+    capitalized_types: true,
+    ignore_case: false,
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    nested_comments: false,
+    quotes: &['"', '\'', '`'],
+    log_levels: false,
+};
+
+const PYTHON: Language = Language {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "self",
+        "True", "try", "while", "with", "yield",
+    ],
+    types: &[
+        "bool", "bytes", "dict", "float", "int", "list", "object", "set", "str", "tuple",
+    ],
+    capitalized_types: true,
+    ignore_case: false,
+    line_comments: &["#"],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"', '\''],
+    log_levels: false,
+};
+
+const SHELL: Language = Language {
+    keywords: &[
+        "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for",
+        "function", "if", "in", "local", "return", "then", "until", "while",
+    ],
+    types: &[],
+    capitalized_types: false,
+    ignore_case: false,
+    line_comments: &["#"],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"', '\''],
+    log_levels: false,
+};
+
+const JSON: Language = Language {
+    keywords: &["true", "false", "null"],
+    types: &[],
+    capitalized_types: false,
+    ignore_case: false,
+    line_comments: &[],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"'],
+    log_levels: false,
+};
+
+const TOML: Language = Language {
+    keywords: &["true", "false"],
+    types: &[],
+    capitalized_types: false,
+    ignore_case: false,
+    line_comments: &["#"],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"', '\''],
+    log_levels: false,
+};
+
+/// Log levels are matched as keywords
+const LOG: Language = Language {
+    keywords: &["debug", "info", "trace", "notice"],
+    types: &[],
+    capitalized_types: false,
+    ignore_case: true,
+    line_comments: &[],
+    block_comment: None,
+    nested_comments: false,
+    quotes: &['"'],
+    log_levels: true,
+};
+
+const LOG_ERRORS: [&str; 5] = ["error", "err", "fatal", "panic", "critical"];
+const LOG_WARNINGS: [&str; 2] = ["warn", "warning"];
+
+/// Language of a code block from its fence tag
+fn language(tag: &str) -> Option<&'static Language> {
+    Some(match tag.to_lowercase().as_str() {
+        "rust" | "rs" => &RUST,
+        "sql" | "psql" | "mysql" | "sqlite" | "pgsql" => &SQL,
+        "js" | "javascript" | "jsx" | "ts" | "typescript" | "tsx" | "java" | "c" | "h" | "cpp"
+        | "c++" | "cs" | "csharp" | "go" | "kotlin" | "kt" | "swift" | "dart" => &JAVASCRIPT,
+        "py" | "python" => &PYTHON,
+        "sh" | "bash" | "zsh" | "shell" | "console" => &SHELL,
+        "json" => &JSON,
+        "toml" | "ini" | "yaml" | "yml" => &TOML,
+        "log" | "logs" => &LOG,
+        _ => return None,
+    })
+}
+
+/// Splits code into highlighted lines. Code without a known language is not highlighted
+pub fn highlight(language_tag: Option<&str>, code: &str) -> Vec<Line> {
+    let language = match language_tag.and_then(language) {
+        Some(language) => language,
+        None => {
+            return code
+                .lines()
+                .map(|line| vec![(TokenKind::Plain, line.to_string())])
+                .collect()
+        }
+    };
+
+    let mut lines: Vec<Line> = vec![vec![]];
+    for (kind, token) in tokens(language, code) {
+        // Comments and strings can span several lines
+        for (i, part) in token.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(vec![]);
+            }
+            if part.is_empty() {
+                continue;
+            }
+
+            let line = lines.last_mut().expect("there is always a line");
+            match line.last_mut() {
+                Some((last_kind, last)) if *last_kind == kind => last.push_str(part),
+                _ => line.push((kind, part.to_string())),
+            }
+        }
+    }
+
+    lines
+}
+
+fn tokens(language: &Language, code: &str) -> Vec<(TokenKind, String)> {
+    let chars = code.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        let c = rest[0];
+
+        let (kind, len) = if let Some(len) = comment(language, rest) {
+            (TokenKind::Comment, len)
+        } else if language.quotes.contains(&c) {
+            (TokenKind::String, string(rest))
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .iter()
+                .position(|c| !c.is_alphanumeric() && *c != '.' && *c != '_')
+                .unwrap_or(rest.len());
+            (TokenKind::Number, len)
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .iter()
+                .position(|c| !c.is_alphanumeric() && *c != '_')
+                .unwrap_or(rest.len());
+            let word = rest[..len].iter().collect::<String>();
+            (word_kind(language, &word, rest.get(len)), len)
+        } else {
+            (TokenKind::Plain, 1)
+        };
+
+        tokens.push((kind, rest[..len].iter().collect()));
+        i += len;
+    }
+
+    tokens
+}
+
+/// Length of the comment at the start of `chars`
+fn comment(language: &Language, chars: &[char]) -> Option<usize> {
+    let starts_with = |pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, c)| chars.get(i) == Some(&c))
+    };
+
+    if language.line_comments.iter().any(|c| starts_with(c)) {
+        return Some(chars.iter().position(|c| *c == '\n').unwrap_or(chars.len()));
+    }
+
+    let (start, end) = language.block_comment?;
+    if !starts_with(start) {
+        return None;
+    }
+
+    let (start, end) = (
+        start.chars().collect::<Vec<_>>(),
+        end.chars().collect::<Vec<_>>(),
+    );
+    let mut depth = 1;
+    let mut i = start.len();
+
+    while i < chars.len() {
+        if chars[i..].starts_with(&end) {
+            depth -= 1;
+            i += end.len();
+            if depth == 0 {
+                return Some(i);
+            }
+        } else if language.nested_comments && chars[i..].starts_with(&start) {
+            depth += 1;
+            i += start.len();
+        } else {
+            i += 1;
+        }
+    }
+
+    // Unterminated comments continue until the end of the code
+    Some(chars.len())
+}
+
+/// Length of the string at the start of `chars`, including its quotes
+fn string(chars: &[char]) -> usize {
+    let quote = chars[0];
+    let mut escaped = false;
+
+    for (i, c) in chars.iter().enumerate().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if *c == quote => return i + 1,
+            // Only backtick strings continue on the next line
+            '\n' if quote != '`' => return i,
+            _ => {}
+        }
+    }
+
+    chars.len()
+}
+
+fn word_kind(language: &Language, word: &str, next: Option<&char>) -> TokenKind {
+    let matches = |words: &[&str]| {
+        if language.ignore_case {
+            words.iter().any(|w| w.eq_ignore_ascii_case(word))
+        } else {
+            words.contains(&word)
+        }
+    };
+
+    if language.log_levels {
+        if matches(&LOG_ERRORS) {
+            return TokenKind::Error;
+        }
+        if matches(&LOG_WARNINGS) {
+            return TokenKind::Warning;
+        }
+    }
+
+    if matches(language.keywords) {
+        TokenKind::Keyword
+    } else if matches(language.types)
+        || (language.capitalized_types && word.starts_with(char::is_uppercase))
+    {
+        TokenKind::Type
+    } else if matches!(next, Some('(') | Some('!')) {
+        // Calls and macros
+        TokenKind::Function
+    } else {
+        TokenKind::Plain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(tokens: &[(TokenKind, &str)]) -> Line {
+        tokens
+            .iter()
+            .map(|(kind, token)| (*kind, token.to_string()))
+            .collect()
+    }
+
+    fn single_line(language_tag: &str, code: &str) -> Line {
+        match highlight(Some(language_tag), code).as_slice() {
+            [line] => line.clone(),
+            lines => panic!("expected a single line, got {lines:?}"),
+        }
+    }
+
+    #[test]
+    fn strings_with_escapes() {
+        assert_eq!(
+            single_line("rust", r#""a \"b\" c\\" x"#),
+            line(&[
+                (TokenKind::String, r#""a \"b\" c\\""#),
+                (TokenKind::Plain, " x"),
+            ])
+        );
+        assert_eq!(
+            single_line("py", r#"'it\'s' "'""#),
+            line(&[
+                (TokenKind::String, r#"'it\'s'"#),
+                (TokenKind::Plain, " "),
+                (TokenKind::String, r#""'""#),
+            ])
+        );
+    }
+
+    #[test]
+    fn unterminated_strings() {
+        assert_eq!(
+            single_line("rust", r#"let s = "abc"#),
+            line(&[
+                (TokenKind::Keyword, "let"),
+                (TokenKind::Plain, " s = "),
+                (TokenKind::String, r#""abc"#),
+            ])
+        );
+        // Strings end at the end of the line, except for backtick strings
+        assert_eq!(
+            highlight(Some("rust"), "\"abc\nlet"),
+            vec![
+                line(&[(TokenKind::String, "\"abc")]),
+                line(&[(TokenKind::Keyword, "let")]),
+            ]
+        );
+        assert_eq!(
+            highlight(Some("js"), "`a\nb`"),
+            vec![
+                line(&[(TokenKind::String, "`a")]),
+                line(&[(TokenKind::String, "b`")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(
+            single_line("rust", "x // y \"z\""),
+            line(&[(TokenKind::Plain, "x "), (TokenKind::Comment, "// y \"z\""),])
+        );
+        assert_eq!(
+            single_line("sql", "a /* b */ c"),
+            line(&[
+                (TokenKind::Plain, "a "),
+                (TokenKind::Comment, "/* b */"),
+                (TokenKind::Plain, " c"),
+            ])
+        );
+        assert_eq!(
+            highlight(Some("rust"), "/* a\nb */ c"),
+            vec![
+                line(&[(TokenKind::Comment, "/* a")]),
+                line(&[(TokenKind::Comment, "b */"), (TokenKind::Plain, " c")]),
+            ]
+        );
+        assert_eq!(
+            single_line("rust", "/* a"),
+            line(&[(TokenKind::Comment, "/* a")])
+        );
+    }
+
+    #[test]
+    fn nested_comments() {
+        assert_eq!(
+            single_line("rust", "/* a /* b */ c */ d"),
+            line(&[
+                (TokenKind::Comment, "/* a /* b */ c */"),
+                (TokenKind::Plain, " d"),
+            ])
+        );
+        // Block comments of languages like C end at the first end
+        assert_eq!(
+            single_line("js", "/* a /* b */ c */"),
+            line(&[
+                (TokenKind::Comment, "/* a /* b */"),
+                (TokenKind::Plain, " c */"),
+            ])
+        );
+    }
+
+    #[test]
+    fn numbers_and_identifiers() {
+        assert_eq!(
+            single_line("rust", "x1 = 12.5e3 + 0xff_u8"),
+            line(&[
+                (TokenKind::Plain, "x1 = "),
+                (TokenKind::Number, "12.5e3"),
+                (TokenKind::Plain, " + "),
+                (TokenKind::Number, "0xff_u8"),
+            ])
+        );
+        assert_eq!(
+            single_line("rust", "_2(u8)"),
+            line(&[
+                (TokenKind::Function, "_2"),
+                (TokenKind::Plain, "("),
+                (TokenKind::Type, "u8"),
+                (TokenKind::Plain, ")"),
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_languages() {
+        let code = "let x = \"a\";\n// b";
+        let plain = vec![
+            line(&[(TokenKind::Plain, "let x = \"a\";")]),
+            line(&[(TokenKind::Plain, "// b")]),
+        ];
+
+        assert_eq!(highlight(Some("unknown"), code), plain);
+        assert_eq!(highlight(None, code), plain);
+        // Tags are matched case insensitively
+        assert_ne!(highlight(Some("RUST"), code), plain);
+    }
+}
@@ -0,0 +1,543 @@
+use std::{iter, mem};
+
+use super::{snowflake::Snowflake, timestamp::TimestampStyle};
+
+/// Characters that lose their meaning when they are escaped with a backslash
+const ESCAPABLE: &[char] = &['\\', '*', '_', '~', '|', '`', '<', '>', '#', '-'];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    /// level from 1 to 3
+    Heading(u8, Vec<Inline>),
+    Quote(Vec<Block>),
+    /// Nested list items have a higher depth
+    ListItem {
+        /// Number of the item in an ordered list
+        number: Option<u32>,
+        depth: u8,
+        content: Vec<Inline>,
+    },
+    CodeBlock {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Bold(Vec<Inline>),
+    Italic(Vec<Inline>),
+    Underline(Vec<Inline>),
+    Strikethrough(Vec<Inline>),
+    /// Spoilers are numbered in the order they appear in the message
+    Spoiler(usize, Vec<Inline>),
+    Code(String),
+    /// `<@id>` or `<@!id>`
+    UserMention(Snowflake),
+    /// `<@&id>`
+    RoleMention(Snowflake),
+    /// `<#id>`
+    ChannelMention(Snowflake),
+    /// `<:name:id>` or `<a:name:id>`
+    CustomEmoji {
+        name: String,
+        id: Snowflake,
+        animated: bool,
+    },
+    /// `<t:unix:style>`, in seconds since the unix epoch
+    Timestamp(i64, TimestampStyle),
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    BoldItalic,
+    Bold,
+    Underline,
+    Strikethrough,
+    Spoiler,
+    Italic,
+}
+
+/// Longer delimiters come first, so `**` is not read as two `*`
+const DELIMITERS: [(&str, Style); 7] = [
+    ("***", Style::BoldItalic),
+    ("**", Style::Bold),
+    ("__", Style::Underline),
+    ("~~", Style::Strikethrough),
+    ("||", Style::Spoiler),
+    ("*", Style::Italic),
+    ("_", Style::Italic),
+];
+
+/// Parses the markdown of a message
+pub fn parse(content: &str) -> Vec<Block> {
+    Parser::default().blocks(content)
+}
+
+/// Ids of the custom emojis in a message, including emojis in code that are not shown
+pub fn custom_emojis(content: &str) -> impl Iterator<Item = Snowflake> + '_ {
+    content.split('<').skip(1).filter_map(|part| {
+        let (content, _) = part.split_once('>')?;
+        match tag(content)? {
+            Inline::CustomEmoji { id, .. } => Some(id),
+            _ => None,
+        }
+    })
+}
+
+#[derive(Default)]
+struct Parser {
+    spoilers: usize,
+}
+
+impl Parser {
+    /// Code blocks can start and end anywhere, so they are split off before the lines are parsed
+    fn blocks(&mut self, content: &str) -> Vec<Block> {
+        let mut blocks = vec![];
+        let mut rest = content;
+
+        while let Some(start) = rest.find("```") {
+            let after = &rest[start + 3..];
+            let end = match after.find("```") {
+                Some(end) => end,
+                None => break,
+            };
+
+            self.lines(&rest[..start], &mut blocks);
+            blocks.push(code_block(&after[..end]));
+
+            rest = &after[end + 3..];
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+        self.lines(rest, &mut blocks);
+
+        blocks
+    }
+
+    fn lines(&mut self, text: &str, blocks: &mut Vec<Block>) {
+        let mut lines = text.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            // Everything after >>> is quoted
+            if let Some(quoted) = line.strip_prefix(">>> ") {
+                let quoted = iter::once(quoted)
+                    .chain(lines.by_ref())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                blocks.push(Block::Quote(self.blocks(&quoted)));
+                break;
+            }
+
+            if let Some(quoted) = quote_line(line) {
+                let mut quoted_lines = vec![quoted];
+                while let Some(quoted) = lines.peek().and_then(|l| quote_line(l)) {
+                    quoted_lines.push(quoted);
+                    lines.next();
+                }
+                blocks.push(Block::Quote(self.blocks(&quoted_lines.join("\n"))));
+                continue;
+            }
+
+            blocks.push(self.line(line));
+        }
+    }
+
+    fn line(&mut self, line: &str) -> Block {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if (1..=3).contains(&level) {
+            if let Some(heading) = line[level..].strip_prefix(' ') {
+                return Block::Heading(level as u8, self.inlines(heading));
+            }
+        }
+
+        let item = line.trim_start();
+        let depth = ((line.len() - item.len()) / 2).min(2) as u8;
+
+        if let Some(content) = item.strip_prefix("- ").or_else(|| item.strip_prefix("* ")) {
+            return Block::ListItem {
+                number: None,
+                depth,
+                content: self.inlines(content),
+            };
+        }
+
+        if let Some((number, content)) = item.split_once(". ") {
+            if let Ok(number) = number.parse::<u32>() {
+                return Block::ListItem {
+                    number: Some(number),
+                    depth,
+                    content: self.inlines(content),
+                };
+            }
+        }
+
+        Block::Paragraph(self.inlines(line))
+    }
+
+    fn inlines(&mut self, text: &str) -> Vec<Inline> {
+        let chars = text.chars().collect::<Vec<_>>();
+        self.parse_inlines(&chars)
+    }
+
+    fn parse_inlines(&mut self, chars: &[char]) -> Vec<Inline> {
+        let mut nodes = vec![];
+        let mut text = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            if let Some((node, len)) = self.inline_at(chars, i) {
+                // Escaped characters stay part of the surrounding text
+                if let Inline::Text(escaped) = node {
+                    text.push_str(&escaped);
+                } else {
+                    if !text.is_empty() {
+                        nodes.push(Inline::Text(mem::take(&mut text)));
+                    }
+                    nodes.push(node);
+                }
+                i += len;
+            } else {
+                text.push(chars[i]);
+                i += 1;
+            }
+        }
+
+        if !text.is_empty() {
+            nodes.push(Inline::Text(text));
+        }
+
+        nodes
+    }
+
+    /// Parses formatted text starting at `i` and returns it with its length in chars
+    fn inline_at(&mut self, chars: &[char], i: usize) -> Option<(Inline, usize)> {
+        let rest = &chars[i..];
+
+        match rest[0] {
+            '\\' => match rest.get(1) {
+                Some(c) if ESCAPABLE.contains(c) => Some((Inline::Text(c.to_string()), 2)),
+                _ => None,
+            },
+            '`' => {
+                let delimiter = if rest.starts_with(&['`', '`']) { 2 } else { 1 };
+                let end = find(rest, delimiter + 1, &rest[..delimiter])?;
+                let code = rest[delimiter..end].iter().collect::<String>();
+
+                Some((Inline::Code(code.trim().to_string()), end + delimiter))
+            }
+            '<' => {
+                let end = find(rest, 1, &['>'])?;
+                let content = rest[1..end].iter().collect::<String>();
+
+                Some((tag(&content)?, end + 1))
+            }
+            _ => DELIMITERS.iter().find_map(|(delimiter, style)| {
+                let delimiter = delimiter.chars().collect::<Vec<_>>();
+                if !rest.starts_with(&delimiter) {
+                    return None;
+                }
+                let len = delimiter.len();
+
+                // Italic text can not start with a space and underscores inside of words are kept
+                if len == 1 {
+                    if rest.get(1).map_or(true, |c| c.is_whitespace()) {
+                        return None;
+                    }
+                    if delimiter[0] == '_' && i > 0 && chars[i - 1].is_alphanumeric() {
+                        return None;
+                    }
+                }
+
+                let end = find(rest, len + 1, &delimiter)?;
+                if delimiter == ['_'] && rest.get(end + 1).map_or(false, |c| c.is_alphanumeric()) {
+                    return None;
+                }
+
+                Some((self.styled(*style, &rest[len..end]), end + len))
+            }),
+        }
+    }
+
+    fn styled(&mut self, style: Style, content: &[char]) -> Inline {
+        match style {
+            Style::BoldItalic => Inline::Bold(vec![Inline::Italic(self.parse_inlines(content))]),
+            Style::Bold => Inline::Bold(self.parse_inlines(content)),
+            Style::Underline => Inline::Underline(self.parse_inlines(content)),
+            Style::Strikethrough => Inline::Strikethrough(self.parse_inlines(content)),
+            Style::Spoiler => {
+                // The outer spoiler gets its number before the nested ones
+                let index = self.spoilers;
+                self.spoilers += 1;
+                Inline::Spoiler(index, self.parse_inlines(content))
+            }
+            Style::Italic => Inline::Italic(self.parse_inlines(content)),
+        }
+    }
+}
+
+/// Position of the first occurrence of `pattern` at or after `from`
+fn find(chars: &[char], from: usize, pattern: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|i| chars[*i..].starts_with(pattern))
+}
+
+/// Parses the content of a mention, custom emoji or timestamp between `<` and `>`
+fn tag(content: &str) -> Option<Inline> {
+    if let Some(id) = content.strip_prefix("@&") {
+        return Some(Inline::RoleMention(id.parse().ok()?));
+    }
+    if let Some(id) = content
+        .strip_prefix("@!")
+        .or_else(|| content.strip_prefix('@'))
+    {
+        return Some(Inline::UserMention(id.parse().ok()?));
+    }
+    if let Some(id) = content.strip_prefix('#') {
+        return Some(Inline::ChannelMention(id.parse().ok()?));
+    }
+    if let Some(timestamp) = content.strip_prefix("t:") {
+        let (unix, style) = match timestamp.split_once(':') {
+            Some((unix, style)) => (unix, TimestampStyle::from_tag(style)?),
+            None => (timestamp, TimestampStyle::default()),
+        };
+        return Some(Inline::Timestamp(unix.parse().ok()?, style));
+    }
+
+    let (animated, emoji) = match content.strip_prefix("a:") {
+        Some(emoji) => (true, emoji),
+        None => (false, content.strip_prefix(':')?),
+    };
+    let (name, id) = emoji.split_once(':')?;
+
+    Some(Inline::CustomEmoji {
+        name: name.to_string(),
+        id: id.parse().ok()?,
+        animated,
+    })
+}
+
+fn quote_line(line: &str) -> Option<&str> {
+    line.strip_prefix("> ")
+        .or_else(|| (line == ">").then_some(""))
+}
+
+/// The first line of a code block is its language if it is a single word
+fn code_block(content: &str) -> Block {
+    if let Some((first, code)) = content.split_once('\n') {
+        if !first.is_empty() && !first.contains(char::is_whitespace) {
+            return Block::CodeBlock {
+                language: Some(first.to_string()),
+                code: code.trim_end_matches('\n').to_string(),
+            };
+        }
+    }
+
+    Block::CodeBlock {
+        language: None,
+        code: content.trim_matches('\n').to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str) -> Inline {
+        Inline::Text(content.to_string())
+    }
+
+    fn paragraph(content: &str) -> Block {
+        Block::Paragraph(vec![text(content)])
+    }
+
+    fn inlines(content: &str) -> Vec<Inline> {
+        match parse(content).as_slice() {
+            [Block::Paragraph(inlines)] => inlines.clone(),
+            blocks => panic!("expected a single paragraph, got {blocks:?}"),
+        }
+    }
+
+    #[test]
+    fn delimiters() {
+        assert_eq!(
+            inlines("**bold** *italic* __underline__ ~~strike~~"),
+            vec![
+                Inline::Bold(vec![text("bold")]),
+                text(" "),
+                Inline::Italic(vec![text("italic")]),
+                text(" "),
+                Inline::Underline(vec![text("underline")]),
+                text(" "),
+                Inline::Strikethrough(vec![text("strike")]),
+            ]
+        );
+        assert_eq!(
+            inlines("***both***"),
+            vec![Inline::Bold(vec![Inline::Italic(vec![text("both")])])]
+        );
+        assert_eq!(
+            inlines("**bold _italic_**"),
+            vec![Inline::Bold(vec![
+                text("bold "),
+                Inline::Italic(vec![text("italic")])
+            ])]
+        );
+    }
+
+    #[test]
+    fn delimiters_that_are_text() {
+        assert_eq!(inlines("snake_case_name"), vec![text("snake_case_name")]);
+        assert_eq!(inlines("a * b *"), vec![text("a * b *")]);
+        assert_eq!(inlines("**unclosed"), vec![text("**unclosed")]);
+    }
+
+    #[test]
+    fn spoilers_are_numbered_in_order() {
+        assert_eq!(
+            inlines("||a|| ||b||"),
+            vec![
+                Inline::Spoiler(0, vec![text("a")]),
+                text(" "),
+                Inline::Spoiler(1, vec![text("b")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(
+            inlines(r"\*not italic\* and \\"),
+            vec![text(r"*not italic* and \")]
+        );
+        assert_eq!(inlines(r"\a"), vec![text(r"\a")]);
+    }
+
+    #[test]
+    fn inline_code_is_not_formatted() {
+        assert_eq!(
+            inlines("`**not bold**` and ``a`b``"),
+            vec![
+                Inline::Code(String::from("**not bold**")),
+                text(" and "),
+                Inline::Code(String::from("a`b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(
+            inlines("<@1> <@!2> <@&3> <#4> <t:1700000000:R> <a:wave:5> <not a tag>"),
+            vec![
+                Inline::UserMention(Snowflake::new(1)),
+                text(" "),
+                Inline::UserMention(Snowflake::new(2)),
+                text(" "),
+                Inline::RoleMention(Snowflake::new(3)),
+                text(" "),
+                Inline::ChannelMention(Snowflake::new(4)),
+                text(" "),
+                Inline::Timestamp(1700000000, TimestampStyle::Relative),
+                text(" "),
+                Inline::CustomEmoji {
+                    name: String::from("wave"),
+                    id: Snowflake::new(5),
+                    animated: true,
+                },
+                text(" <not a tag>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_fences() {
+        assert_eq!(
+            parse("before\n```rust\nfn main() {}\n```\nafter"),
+            vec![
+                paragraph("before"),
+                Block::CodeBlock {
+                    language: Some(String::from("rust")),
+                    code: String::from("fn main() {}"),
+                },
+                paragraph("after"),
+            ]
+        );
+        assert_eq!(
+            parse("```\n**code**\n```"),
+            vec![Block::CodeBlock {
+                language: None,
+                code: String::from("**code**"),
+            }]
+        );
+        assert_eq!(
+            parse("```one line```"),
+            vec![Block::CodeBlock {
+                language: None,
+                code: String::from("one line"),
+            }]
+        );
+        assert_eq!(parse("```unclosed"), vec![paragraph("```unclosed")]);
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(
+            parse("> a\n> b\nc"),
+            vec![
+                Block::Quote(vec![paragraph("a"), paragraph("b")]),
+                paragraph("c"),
+            ]
+        );
+        assert_eq!(
+            parse(">>> a\nb"),
+            vec![Block::Quote(vec![paragraph("a"), paragraph("b")])]
+        );
+        assert_eq!(parse(">not a quote"), vec![paragraph(">not a quote")]);
+    }
+
+    #[test]
+    fn lists_and_headings() {
+        assert_eq!(
+            parse("- one\n  - two\n* three\n3. four"),
+            vec![
+                Block::ListItem {
+                    number: None,
+                    depth: 0,
+                    content: vec![text("one")],
+                },
+                Block::ListItem {
+                    number: None,
+                    depth: 1,
+                    content: vec![text("two")],
+                },
+                Block::ListItem {
+                    number: None,
+                    depth: 0,
+                    content: vec![text("three")],
+                },
+                Block::ListItem {
+                    number: Some(3),
+                    depth: 0,
+                    content: vec![text("four")],
+                },
+            ]
+        );
+        assert_eq!(
+            parse("# Title\n#not a heading\n#### four"),
+            vec![
+                Block::Heading(1, vec![text("Title")]),
+                paragraph("#not a heading"),
+                paragraph("#### four"),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_emojis_include_code() {
+        assert_eq!(
+            custom_emojis("<:one:1> `<a:two:2>` <@3>").collect::<Vec<_>>(),
+            vec![Snowflake::new(1), Snowflake::new(2)]
+        );
+    }
+}
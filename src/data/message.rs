@@ -0,0 +1,290 @@
+use serde::Deserialize;
+use time::OffsetDateTime;
+
+use super::{markdown, snowflake::Snowflake};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageStatus {
+    Sent,
+    Pending,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageKind {
+    Default,
+    RecipientAdd,
+    RecipientRemove,
+    Call,
+    ChannelNameChange,
+    ChannelIconChange,
+    ChannelPinnedMessage,
+    UserJoin,
+    GuildBoost,
+    ChannelFollowAdd,
+    ThreadCreated,
+    Reply,
+    ChatInputCommand,
+    ThreadStarterMessage,
+    ContextMenuCommand,
+    AutoModerationAction,
+    Unknown(u16),
+}
+
+impl MessageKind {
+    /// System messages are generated by discord and have no content written by their author
+    pub fn is_system(&self) -> bool {
+        !matches!(
+            self,
+            MessageKind::Default
+                | MessageKind::Reply
+                | MessageKind::ChatInputCommand
+                | MessageKind::ContextMenuCommand
+        )
+    }
+}
+
+impl From<u16> for MessageKind {
+    fn from(kind: u16) -> Self {
+        match kind {
+            0 => MessageKind::Default,
+            1 => MessageKind::RecipientAdd,
+            2 => MessageKind::RecipientRemove,
+            3 => MessageKind::Call,
+            4 => MessageKind::ChannelNameChange,
+            5 => MessageKind::ChannelIconChange,
+            6 => MessageKind::ChannelPinnedMessage,
+            7 => MessageKind::UserJoin,
+            8..=11 => MessageKind::GuildBoost,
+            12 => MessageKind::ChannelFollowAdd,
+            18 => MessageKind::ThreadCreated,
+            19 => MessageKind::Reply,
+            20 => MessageKind::ChatInputCommand,
+            21 => MessageKind::ThreadStarterMessage,
+            23 => MessageKind::ContextMenuCommand,
+            24 => MessageKind::AutoModerationAction,
+            kind => MessageKind::Unknown(kind),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct MessageFlags(pub u64);
+
+impl MessageFlags {
+    pub const CROSSPOSTED: Self = Self(1 << 0);
+    pub const IS_CROSSPOST: Self = Self(1 << 1);
+    pub const SUPPRESS_EMBEDS: Self = Self(1 << 2);
+    pub const SOURCE_MESSAGE_DELETED: Self = Self(1 << 3);
+    pub const URGENT: Self = Self(1 << 4);
+    pub const HAS_THREAD: Self = Self(1 << 5);
+    pub const EPHEMERAL: Self = Self(1 << 6);
+    pub const LOADING: Self = Self(1 << 7);
+    pub const FAILED_TO_MENTION_SOME_ROLES_IN_THREAD: Self = Self(1 << 8);
+    pub const SUPPRESS_NOTIFICATIONS: Self = Self(1 << 12);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Attachment {
+    pub id: Snowflake,
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub size: u64,
+    pub url: String,
+    pub proxy_url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Embed {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub color: Option<u32>,
+    pub author: Option<EmbedAuthor>,
+    pub footer: Option<EmbedFooter>,
+    pub image: Option<EmbedMedia>,
+    pub thumbnail: Option<EmbedMedia>,
+    pub video: Option<EmbedMedia>,
+    #[serde(default)]
+    pub fields: Vec<EmbedField>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbedAuthor {
+    pub name: String,
+    pub url: Option<String>,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbedFooter {
+    pub text: String,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbedMedia {
+    pub url: Option<String>,
+    pub proxy_url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub inline: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReference {
+    pub message_id: Option<Snowflake>,
+    pub channel_id: Option<Snowflake>,
+    pub guild_id: Option<Snowflake>,
+}
+
+/// Unicode emojis only have a name, the name of a deleted custom emoji can be missing
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionEmoji {
+    pub id: Option<Snowflake>,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub animated: bool,
+}
+
+impl ReactionEmoji {
+    /// Representation of the emoji in reaction urls, `name:id` for custom emojis
+    pub fn url_name(&self) -> String {
+        let name = self.name.as_deref().unwrap_or("_");
+        match self.id {
+            Some(id) => format!("{name}:{id}"),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Custom emojis are compared by id because they can be renamed
+impl PartialEq for ReactionEmoji {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.id, other.id) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => self.name == other.name,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reaction {
+    pub count: u32,
+    /// Whether the current user reacted with this emoji
+    pub me: bool,
+    pub emoji: ReactionEmoji,
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// The nonce is used as the id until the message was sent
+    pub id: Snowflake,
+    pub kind: MessageKind,
+    pub author_id: Snowflake,
+    pub content: String,
+    pub timestamp: OffsetDateTime,
+    pub edited_timestamp: Option<OffsetDateTime>,
+    pub attachments: Vec<Attachment>,
+    pub embeds: Vec<Embed>,
+    /// Ids of the mentioned users
+    pub mentions: Vec<Snowflake>,
+    pub mention_roles: Vec<Snowflake>,
+    pub mention_everyone: bool,
+    pub reference: Option<MessageReference>,
+    /// The message this message replies to. Is `None` if it was deleted
+    pub referenced_message: Option<Box<Message>>,
+    pub pinned: bool,
+    pub flags: MessageFlags,
+    pub reactions: Vec<Reaction>,
+    pub nonce: Option<String>,
+    pub status: MessageStatus,
+    pub deleted: bool,
+}
+
+impl Message {
+    /// Creates a message that is shown until discord created the actual message
+    pub fn pending(nonce: Snowflake, author_id: Snowflake, content: String) -> Self {
+        Self {
+            id: nonce,
+            kind: MessageKind::Default,
+            author_id,
+            content,
+            timestamp: OffsetDateTime::now_utc(),
+            edited_timestamp: None,
+            attachments: vec![],
+            embeds: vec![],
+            mentions: vec![],
+            mention_roles: vec![],
+            mention_everyone: false,
+            reference: None,
+            referenced_message: None,
+            pinned: false,
+            flags: MessageFlags::default(),
+            reactions: vec![],
+            nonce: Some(nonce.to_string()),
+            status: MessageStatus::Pending,
+            deleted: false,
+        }
+    }
+}
+
+impl Message {
+    /// Adds a reaction of a user. `me` is set if the current user reacted
+    pub fn add_reaction(&mut self, emoji: ReactionEmoji, me: bool) {
+        if let Some(reaction) = self.reactions.iter_mut().find(|r| r.emoji == emoji) {
+            // The current user can only react once with each emoji
+            if !(me && reaction.me) {
+                reaction.count += 1;
+            }
+            reaction.me |= me;
+        } else {
+            self.reactions.push(Reaction {
+                count: 1,
+                me,
+                emoji,
+            });
+        }
+    }
+
+    /// Removes a reaction of a user. `me` is set if it was the reaction of the current user
+    pub fn remove_reaction(&mut self, emoji: &ReactionEmoji, me: bool) {
+        if let Some(reaction) = self.reactions.iter_mut().find(|r| r.emoji == *emoji) {
+            if !me || reaction.me {
+                reaction.count = reaction.count.saturating_sub(1);
+            }
+            if me {
+                reaction.me = false;
+            }
+        }
+        self.reactions.retain(|r| r.count > 0);
+    }
+
+    /// Removes all reactions with an emoji
+    pub fn remove_emoji_reactions(&mut self, emoji: &ReactionEmoji) {
+        self.reactions.retain(|r| r.emoji != *emoji);
+    }
+
+    /// Ids of the custom emojis in the content and reactions
+    pub fn custom_emojis(&self) -> Vec<Snowflake> {
+        markdown::custom_emojis(&self.content)
+            .chain(self.reactions.iter().filter_map(|r| r.emoji.id))
+            .collect()
+    }
+}
@@ -1,3 +1,10 @@
+pub mod guild;
+pub mod highlight;
+pub mod markdown;
+pub mod message;
+pub mod permissions;
 pub mod settings;
+pub mod snowflake;
 pub mod state;
+pub mod timestamp;
 pub mod user;
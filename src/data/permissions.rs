@@ -0,0 +1,428 @@
+use std::ops::{BitAnd, BitOr, Not};
+
+use super::{
+    guild::{Guild, GuildChannel, Member, OverwriteKind},
+    snowflake::Snowflake,
+};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions(pub u64);
+
+impl Permissions {
+    pub const NONE: Self = Self(0);
+    pub const ALL: Self = Self(u64::MAX);
+
+    pub const CREATE_INSTANT_INVITE: Self = Self(1 << 0);
+    pub const KICK_MEMBERS: Self = Self(1 << 1);
+    pub const BAN_MEMBERS: Self = Self(1 << 2);
+    pub const ADMINISTRATOR: Self = Self(1 << 3);
+    pub const MANAGE_CHANNELS: Self = Self(1 << 4);
+    pub const MANAGE_GUILD: Self = Self(1 << 5);
+    pub const ADD_REACTIONS: Self = Self(1 << 6);
+    pub const VIEW_AUDIT_LOG: Self = Self(1 << 7);
+    pub const PRIORITY_SPEAKER: Self = Self(1 << 8);
+    pub const STREAM: Self = Self(1 << 9);
+    pub const VIEW_CHANNEL: Self = Self(1 << 10);
+    pub const SEND_MESSAGES: Self = Self(1 << 11);
+    pub const SEND_TTS_MESSAGES: Self = Self(1 << 12);
+    pub const MANAGE_MESSAGES: Self = Self(1 << 13);
+    pub const EMBED_LINKS: Self = Self(1 << 14);
+    pub const ATTACH_FILES: Self = Self(1 << 15);
+    pub const READ_MESSAGE_HISTORY: Self = Self(1 << 16);
+    pub const MENTION_EVERYONE: Self = Self(1 << 17);
+    pub const USE_EXTERNAL_EMOJIS: Self = Self(1 << 18);
+    pub const VIEW_GUILD_INSIGHTS: Self = Self(1 << 19);
+    pub const CONNECT: Self = Self(1 << 20);
+    pub const SPEAK: Self = Self(1 << 21);
+    pub const MUTE_MEMBERS: Self = Self(1 << 22);
+    pub const DEAFEN_MEMBERS: Self = Self(1 << 23);
+    pub const MOVE_MEMBERS: Self = Self(1 << 24);
+    pub const USE_VAD: Self = Self(1 << 25);
+    pub const CHANGE_NICKNAME: Self = Self(1 << 26);
+    pub const MANAGE_NICKNAMES: Self = Self(1 << 27);
+    pub const MANAGE_ROLES: Self = Self(1 << 28);
+    pub const MANAGE_WEBHOOKS: Self = Self(1 << 29);
+    pub const MANAGE_GUILD_EXPRESSIONS: Self = Self(1 << 30);
+    pub const USE_APPLICATION_COMMANDS: Self = Self(1 << 31);
+    pub const REQUEST_TO_SPEAK: Self = Self(1 << 32);
+    pub const MANAGE_EVENTS: Self = Self(1 << 33);
+    pub const MANAGE_THREADS: Self = Self(1 << 34);
+    pub const CREATE_PUBLIC_THREADS: Self = Self(1 << 35);
+    pub const CREATE_PRIVATE_THREADS: Self = Self(1 << 36);
+    pub const USE_EXTERNAL_STICKERS: Self = Self(1 << 37);
+    pub const SEND_MESSAGES_IN_THREADS: Self = Self(1 << 38);
+    pub const USE_EMBEDDED_ACTIVITIES: Self = Self(1 << 39);
+    pub const MODERATE_MEMBERS: Self = Self(1 << 40);
+
+    /// What everyone can do in direct messages and groups
+    pub const PRIVATE_CHANNEL: Self = Self(
+        Self::ADD_REACTIONS.0
+            | Self::VIEW_CHANNEL.0
+            | Self::SEND_MESSAGES.0
+            | Self::EMBED_LINKS.0
+            | Self::ATTACH_FILES.0
+            | Self::READ_MESSAGE_HISTORY.0
+            | Self::USE_EXTERNAL_EMOJIS.0,
+    );
+
+    /// Permissions that are lost together with SEND_MESSAGES
+    const SEND_MESSAGES_DEPENDENT: Self = Self(
+        Self::SEND_TTS_MESSAGES.0
+            | Self::EMBED_LINKS.0
+            | Self::ATTACH_FILES.0
+            | Self::MENTION_EVERYONE.0,
+    );
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Applies the denied and then the allowed permissions of an overwrite
+    fn overwrite(self, allow: u64, deny: u64) -> Self {
+        Self((self.0 & !deny) | allow)
+    }
+}
+
+impl BitOr for Permissions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for Permissions {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for Permissions {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+/// Guild wide permissions of a member from the @everyone role and its own roles.
+/// A member of `None` only has the permissions of @everyone
+pub fn base_permissions(guild: &Guild, user_id: Snowflake, member: Option<&Member>) -> Permissions {
+    if guild.owner_id == Some(user_id) {
+        return Permissions::ALL;
+    }
+
+    // The @everyone role has the same id as the guild
+    let permissions = guild
+        .roles
+        .iter()
+        .filter(|r| r.id == guild.id || member.map_or(false, |m| m.roles.contains(&r.id)))
+        .fold(Permissions::NONE, |permissions, r| {
+            permissions | Permissions(r.permissions)
+        });
+
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        Permissions::ALL
+    } else {
+        permissions
+    }
+}
+
+/// Permissions of a member in a channel. Overwrites are applied in the order @everyone,
+/// roles and member, each one taking precedence over the previous ones
+pub fn channel_permissions(
+    guild: &Guild,
+    channel: &GuildChannel,
+    user_id: Snowflake,
+    member: Option<&Member>,
+) -> Permissions {
+    let mut permissions = base_permissions(guild, user_id, member);
+    if permissions.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::ALL;
+    }
+
+    let overwrites = &channel.permission_overwrites;
+
+    if let Some(everyone) = overwrites
+        .iter()
+        .find(|o| o.kind == OverwriteKind::Role && o.id == guild.id)
+    {
+        permissions = permissions.overwrite(everyone.allow, everyone.deny);
+    }
+
+    // Role overwrites are combined, so an allow of one role beats a deny of another
+    let (allow, deny) = overwrites
+        .iter()
+        .filter(|o| {
+            o.kind == OverwriteKind::Role && member.map_or(false, |m| m.roles.contains(&o.id))
+        })
+        .fold((0, 0), |(allow, deny), o| (allow | o.allow, deny | o.deny));
+    permissions = permissions.overwrite(allow, deny);
+
+    if let Some(own) = overwrites
+        .iter()
+        .find(|o| o.kind == OverwriteKind::Member && o.id == user_id)
+    {
+        permissions = permissions.overwrite(own.allow, own.deny);
+    }
+
+    // Channels that can not be viewed grant nothing, and sending related permissions need SEND_MESSAGES
+    if !permissions.contains(Permissions::VIEW_CHANNEL) {
+        Permissions::NONE
+    } else if !permissions.contains(Permissions::SEND_MESSAGES) {
+        permissions & !Permissions::SEND_MESSAGES_DEPENDENT
+    } else {
+        permissions
+    }
+}
+
+/// Permissions of the current user in a channel of the guild
+pub fn own_channel_permissions(
+    guild: &Guild,
+    channel: &GuildChannel,
+    user_id: Snowflake,
+) -> Permissions {
+    channel_permissions(guild, channel, user_id, guild.member.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::data::guild::{GuildChannelKind, PermissionOverwrite, Role};
+
+    const GUILD: Snowflake = Snowflake::new(1);
+    const OWNER: Snowflake = Snowflake::new(2);
+    const USER: Snowflake = Snowflake::new(3);
+    const MODERATOR: Snowflake = Snowflake::new(10);
+    const MUTED: Snowflake = Snowflake::new(11);
+
+    fn role(id: Snowflake, permissions: Permissions) -> Role {
+        Role {
+            id,
+            name: String::new(),
+            color: 0,
+            position: 0,
+            permissions: permissions.0,
+            hoist: false,
+            mentionable: false,
+        }
+    }
+
+    /// @everyone can view channels and read history, moderators can also send and manage messages
+    fn guild() -> Guild {
+        Guild {
+            id: GUILD,
+            name: String::new(),
+            icon: None,
+            icon_handle: None,
+            owner_id: Some(OWNER),
+            channels: vec![],
+            roles: vec![
+                role(
+                    GUILD,
+                    Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY,
+                ),
+                role(
+                    MODERATOR,
+                    Permissions::SEND_MESSAGES | Permissions::MANAGE_MESSAGES,
+                ),
+                role(MUTED, Permissions::NONE),
+            ],
+            emojis: vec![],
+            member: None,
+            members: HashMap::new(),
+            unavailable: false,
+        }
+    }
+
+    fn member(roles: Vec<Snowflake>) -> Member {
+        Member { nick: None, roles }
+    }
+
+    fn overwrite(
+        id: Snowflake,
+        kind: OverwriteKind,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> PermissionOverwrite {
+        PermissionOverwrite {
+            id,
+            kind,
+            allow: allow.0,
+            deny: deny.0,
+        }
+    }
+
+    fn channel(permission_overwrites: Vec<PermissionOverwrite>) -> GuildChannel {
+        GuildChannel {
+            id: Snowflake::new(100),
+            kind: GuildChannelKind::Text,
+            name: String::new(),
+            position: 0,
+            parent_id: None,
+            topic: None,
+            nsfw: false,
+            permission_overwrites,
+            last_message_id: None,
+        }
+    }
+
+    #[test]
+    fn base_permissions_combine_everyone_and_roles() {
+        let guild = guild();
+
+        assert_eq!(
+            base_permissions(&guild, USER, Some(&member(vec![]))),
+            Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY
+        );
+        assert_eq!(
+            base_permissions(&guild, USER, Some(&member(vec![MODERATOR]))),
+            Permissions::VIEW_CHANNEL
+                | Permissions::READ_MESSAGE_HISTORY
+                | Permissions::SEND_MESSAGES
+                | Permissions::MANAGE_MESSAGES
+        );
+    }
+
+    #[test]
+    fn administrators_and_the_owner_have_all_permissions() {
+        let mut guild = guild();
+        guild
+            .roles
+            .push(role(Snowflake::new(12), Permissions::ADMINISTRATOR));
+        let admin = member(vec![Snowflake::new(12)]);
+        // Even a channel that denies everything to everyone
+        let channel = channel(vec![overwrite(
+            GUILD,
+            OverwriteKind::Role,
+            Permissions::NONE,
+            Permissions::ALL,
+        )]);
+
+        assert_eq!(
+            base_permissions(&guild, USER, Some(&admin)),
+            Permissions::ALL
+        );
+        assert_eq!(
+            channel_permissions(&guild, &channel, USER, Some(&admin)),
+            Permissions::ALL
+        );
+        assert_eq!(
+            channel_permissions(&guild, &channel, OWNER, None),
+            Permissions::ALL
+        );
+    }
+
+    #[test]
+    fn overwrites_apply_everyone_then_roles_then_member() {
+        let guild = guild();
+
+        // @everyone is allowed to send, the muted role denies it
+        let channel = channel(vec![
+            overwrite(
+                GUILD,
+                OverwriteKind::Role,
+                Permissions::SEND_MESSAGES,
+                Permissions::NONE,
+            ),
+            overwrite(
+                MUTED,
+                OverwriteKind::Role,
+                Permissions::NONE,
+                Permissions::SEND_MESSAGES,
+            ),
+        ]);
+        let muted = member(vec![MUTED]);
+        assert!(
+            channel_permissions(&guild, &channel, USER, Some(&member(vec![])))
+                .contains(Permissions::SEND_MESSAGES)
+        );
+        assert!(!channel_permissions(&guild, &channel, USER, Some(&muted))
+            .contains(Permissions::SEND_MESSAGES));
+
+        // Role allows beat role denies
+        let mut with_moderator = channel.clone();
+        with_moderator.permission_overwrites.push(overwrite(
+            MODERATOR,
+            OverwriteKind::Role,
+            Permissions::SEND_MESSAGES,
+            Permissions::NONE,
+        ));
+        let muted_moderator = member(vec![MUTED, MODERATOR]);
+        assert!(
+            channel_permissions(&guild, &with_moderator, USER, Some(&muted_moderator))
+                .contains(Permissions::SEND_MESSAGES)
+        );
+
+        // The member overwrite beats the role overwrites
+        let mut with_member = channel;
+        with_member.permission_overwrites.push(overwrite(
+            USER,
+            OverwriteKind::Member,
+            Permissions::SEND_MESSAGES,
+            Permissions::NONE,
+        ));
+        assert!(
+            channel_permissions(&guild, &with_member, USER, Some(&muted))
+                .contains(Permissions::SEND_MESSAGES)
+        );
+    }
+
+    #[test]
+    fn denied_view_channel_clears_everything() {
+        let guild = guild();
+        let channel = channel(vec![overwrite(
+            GUILD,
+            OverwriteKind::Role,
+            Permissions::SEND_MESSAGES,
+            Permissions::VIEW_CHANNEL,
+        )]);
+
+        assert_eq!(
+            channel_permissions(&guild, &channel, USER, Some(&member(vec![MODERATOR]))),
+            Permissions::NONE
+        );
+    }
+
+    #[test]
+    fn denied_send_messages_clears_dependent_permissions() {
+        let guild = guild();
+        let channel = channel(vec![overwrite(
+            GUILD,
+            OverwriteKind::Role,
+            Permissions::EMBED_LINKS | Permissions::ATTACH_FILES | Permissions::MENTION_EVERYONE,
+            Permissions::NONE,
+        )]);
+
+        let permissions = channel_permissions(&guild, &channel, USER, Some(&member(vec![])));
+        assert!(permissions.contains(Permissions::VIEW_CHANNEL));
+        assert!(!permissions.contains(Permissions::EMBED_LINKS));
+        assert!(!permissions.contains(Permissions::ATTACH_FILES));
+        assert!(!permissions.contains(Permissions::MENTION_EVERYONE));
+    }
+
+    #[test]
+    fn unknown_member_only_has_everyone_permissions() {
+        let mut guild = guild();
+        let channel = channel(vec![overwrite(
+            MODERATOR,
+            OverwriteKind::Role,
+            Permissions::SEND_MESSAGES,
+            Permissions::NONE,
+        )]);
+
+        assert_eq!(
+            own_channel_permissions(&guild, &channel, USER),
+            Permissions::VIEW_CHANNEL | Permissions::READ_MESSAGE_HISTORY
+        );
+
+        guild.member = Some(member(vec![MODERATOR]));
+        assert!(
+            own_channel_permissions(&guild, &channel, USER).contains(Permissions::SEND_MESSAGES)
+        );
+    }
+}
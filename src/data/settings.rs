@@ -21,6 +21,19 @@ pub struct Settings {
     pub theme: String,
     pub active_account: String,
     pub accounts: Vec<String>,
+    /// Keep deleted messages in the chat and mark them as deleted instead of removing them
+    #[serde(default)]
+    pub show_deleted_messages: bool,
+    /// Show other users when the current user is typing
+    #[serde(default = "default_true")]
+    pub send_typing_indicator: bool,
+    /// Show every message in one line with its author instead of grouping messages under the author
+    #[serde(default)]
+    pub compact_messages: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Settings {
@@ -63,6 +76,9 @@ impl Default for Settings {
             theme: ThemeData::dark().id,
             active_account: String::from(""),
             accounts: vec![],
+            show_deleted_messages: false,
+            send_typing_indicator: true,
+            compact_messages: false,
         }
     }
 }
@@ -0,0 +1,109 @@
+use std::{
+    fmt,
+    num::ParseIntError,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use time::OffsetDateTime;
+
+/// Milliseconds between the unix epoch and the first second of 2015
+const DISCORD_EPOCH: u64 = 1420070400000;
+
+/// Increment of the snowflakes created by this process
+static INCREMENT: AtomicU64 = AtomicU64::new(0);
+
+/// Unique discord id that contains the time it was created at
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Snowflake(u64);
+
+impl Snowflake {
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Creates a snowflake for the current time. Used for nonces, so pending messages are sorted last.
+    /// The increment keeps snowflakes created in the same millisecond unique
+    pub fn now() -> Self {
+        let ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(DISCORD_EPOCH);
+        let increment = INCREMENT.fetch_add(1, Ordering::Relaxed) & 0xFFF;
+
+        Self((ms.saturating_sub(DISCORD_EPOCH) << 22) | increment)
+    }
+
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Milliseconds since the unix epoch
+    pub fn timestamp(self) -> u64 {
+        (self.0 >> 22) + DISCORD_EPOCH
+    }
+
+    pub fn created_at(self) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp_nanos(self.timestamp() as i128 * 1_000_000)
+            .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+    }
+
+    pub fn worker_id(self) -> u8 {
+        ((self.0 & 0x3E0000) >> 17) as u8
+    }
+
+    pub fn process_id(self) -> u8 {
+        ((self.0 & 0x1F000) >> 12) as u8
+    }
+
+    /// Incremented for every id generated on the same process
+    pub fn increment(self) -> u16 {
+        (self.0 & 0xFFF) as u16
+    }
+}
+
+impl fmt::Display for Snowflake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Snowflake {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl Serialize for Snowflake {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Snowflake {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Snowflake;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a snowflake as a string or an integer")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(de::Error::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(Snowflake(v))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
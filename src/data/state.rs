@@ -1,10 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
 
 use iced::widget::image;
 
 use crate::api::gateway::Gateway;
 
-use super::user::User;
+use super::{
+    guild::{Guild, Member, Role},
+    message::{Message, MessageStatus},
+    snowflake::Snowflake,
+    user::{CustomStatus, User},
+};
+
+/// How long a user is shown as typing after it started typing
+const TYPING_DURATION: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 pub enum ConnectionState {
@@ -15,47 +26,356 @@ pub enum ConnectionState {
 
 #[derive(Debug, Clone)]
 pub struct State {
-    pub user_id: String,
+    pub user_id: Snowflake,
     pub relationships: Vec<Relationship>,
     pub private_channels: Vec<PrivateChannel>,
-    pub user_cache: HashMap<String, User>,
-    pub message_cache: HashMap<String, Vec<Message>>,
+    pub guilds: Vec<Guild>,
+    pub user_cache: HashMap<Snowflake, User>,
+    /// Custom status of the current user
+    pub custom_status: Option<CustomStatus>,
+    pub message_cache: HashMap<Snowflake, BTreeMap<Snowflake, Message>>,
+    pub message_history: HashMap<Snowflake, MessageHistory>,
+    /// Last read message of each channel
+    pub read_states: HashMap<Snowflake, Snowflake>,
+    /// Last read message of each channel before it was opened. The new messages divider
+    /// is shown after it, so it does not disappear while the channel is read
+    pub unread_dividers: HashMap<Snowflake, Snowflake>,
+    /// Users that are typing in each channel and when they started typing
+    pub typing: HashMap<Snowflake, HashMap<Snowflake, Instant>>,
+    /// Images of custom emojis. Emojis that are still loading have no image
+    pub emojis: HashMap<Snowflake, Option<image::Handle>>,
 }
 
 impl State {
     pub fn new(
-        user_id: String,
+        user_id: Snowflake,
         relationships: Vec<Relationship>,
         private_channels: Vec<PrivateChannel>,
-        user_cache: HashMap<String, User>,
+        guilds: Vec<Guild>,
+        user_cache: HashMap<Snowflake, User>,
     ) -> Self {
         State {
             user_id,
             relationships,
             private_channels,
+            guilds,
             user_cache,
+            custom_status: None,
             message_cache: HashMap::with_capacity(50),
+            message_history: HashMap::with_capacity(50),
+            read_states: HashMap::new(),
+            unread_dividers: HashMap::new(),
+            typing: HashMap::new(),
+            emojis: HashMap::new(),
         }
     }
 
-    pub fn insert_message(&mut self, channel_id: String, msg: Message) {
-        self.message_cache
-            .entry(channel_id)
-            .and_modify(|msgs| msgs.push(msg.clone()))
-            .or_insert_with(|| {
-                let mut v = Vec::with_capacity(50);
-                v.push(msg);
-                v
+    pub fn guild(&self, id: Snowflake) -> Option<&Guild> {
+        self.guilds.iter().find(|g| g.id == id)
+    }
+
+    /// The guild a channel belongs to, `None` for private channels
+    pub fn channel_guild(&self, channel_id: Snowflake) -> Option<&Guild> {
+        self.guilds
+            .iter()
+            .find(|g| g.channels.iter().any(|c| c.id == channel_id))
+    }
+
+    /// Name of a guild channel or group
+    pub fn channel_name(&self, channel_id: Snowflake) -> Option<&str> {
+        self.guilds
+            .iter()
+            .flat_map(|g| g.channels.iter())
+            .find(|c| c.id == channel_id)
+            .map(|c| c.name.as_str())
+            .or_else(|| {
+                self.private_channels
+                    .iter()
+                    .find(|c| c.id == channel_id)
+                    .and_then(|c| c.name.as_deref())
+            })
+    }
+
+    /// Nickname of a user in the guild of a channel, or its username
+    pub fn display_name(&self, channel_id: Snowflake, user_id: Snowflake) -> Option<&str> {
+        self.channel_guild(channel_id)
+            .and_then(|g| g.members.get(&user_id)?.nick.as_deref())
+            .or_else(|| self.user_cache.get(&user_id).map(|u| u.username.as_str()))
+    }
+
+    pub fn role(&self, role_id: Snowflake) -> Option<&Role> {
+        self.guilds
+            .iter()
+            .flat_map(|g| g.roles.iter())
+            .find(|r| r.id == role_id)
+    }
+
+    /// Whether a message mentions the current user directly, with one of its roles or with @everyone
+    pub fn mentions_me(&self, channel_id: Snowflake, msg: &Message) -> bool {
+        if msg.mention_everyone || msg.mentions.contains(&self.user_id) {
+            return true;
+        }
+
+        self.channel_guild(channel_id)
+            .and_then(|g| g.member.as_ref())
+            .map_or(false, |member| {
+                member.roles.iter().any(|r| msg.mention_roles.contains(r))
+            })
+    }
+
+    /// Marks custom emojis that are not loaded yet as loading and returns them
+    pub fn emojis_to_load(&mut self, ids: impl IntoIterator<Item = Snowflake>) -> Vec<Snowflake> {
+        ids.into_iter()
+            .filter(|id| {
+                if self.emojis.contains_key(id) {
+                    false
+                } else {
+                    self.emojis.insert(*id, None);
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// Adds a guild or replaces it, keeping its position, if it was unavailable or sent again
+    pub fn insert_guild(&mut self, mut guild: Guild) {
+        if let Some(g) = self.guilds.iter_mut().find(|g| g.id == guild.id) {
+            if g.icon == guild.icon {
+                guild.icon_handle = g.icon_handle.take();
+            }
+            // The members sent with a guild do not always contain the current user
+            if guild.member.is_none() {
+                guild.member = g.member.take();
+            }
+            for (id, member) in g.members.drain() {
+                guild.members.entry(id).or_insert(member);
+            }
+            *g = guild;
+        } else {
+            self.guilds.push(guild);
+        }
+    }
+
+    /// Caches a guild member, e.g. the author of a message
+    pub fn cache_member(&mut self, guild_id: Snowflake, user_id: Snowflake, member: Member) {
+        let own_id = self.user_id;
+        self.update_guild(guild_id, |guild| {
+            if user_id == own_id {
+                guild.member = Some(member.clone());
+            }
+            guild.members.insert(user_id, member);
+        });
+    }
+
+    /// Applies a change to a guild. Returns false if the guild is not known
+    pub fn update_guild(&mut self, id: Snowflake, f: impl FnOnce(&mut Guild)) -> bool {
+        if let Some(guild) = self.guilds.iter_mut().find(|g| g.id == id) {
+            f(guild);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn remove_guild(&mut self, id: Snowflake) {
+        self.guilds.retain(|g| g.id != id);
+    }
+
+    /// Caches a user or updates the cached user while keeping its presence and loaded avatar.
+    /// Returns true if the avatar of the user still has to be loaded
+    pub fn cache_user(&mut self, user: User) -> bool {
+        if let Some(cached) = self.user_cache.get_mut(&user.id) {
+            let avatar_changed = cached.avatar != user.avatar;
+            cached.username = user.username;
+            cached.discriminator = user.discriminator;
+            cached.accent_color = user.accent_color;
+            cached.avatar = user.avatar;
+            if avatar_changed {
+                cached.avatar_handle = None;
+            }
+
+            cached.avatar_handle.is_none()
+        } else {
+            self.user_cache.insert(user.id, user);
+            true
+        }
+    }
+
+    pub fn set_relationship(&mut self, id: Snowflake, kind: RelationshipKind) {
+        if let Some(relationship) = self.relationships.iter_mut().find(|r| r.id == id) {
+            relationship.kind = kind;
+        } else {
+            self.relationships.push(Relationship { id, kind });
+        }
+    }
+
+    pub fn remove_relationship(&mut self, id: Snowflake) {
+        self.relationships.retain(|r| r.id != id);
+    }
+
+    /// The direct message channel with a user, if one was opened before
+    pub fn direct_message_channel(&self, user_id: Snowflake) -> Option<&PrivateChannel> {
+        self.private_channels.iter().find(|c| {
+            matches!(c.kind, PrivateChannelKind::DirectMessage) && c.recipients == [user_id]
+        })
+    }
+
+    pub fn insert_message(&mut self, channel_id: Snowflake, msg: Message) {
+        self.insert_messages(channel_id, [msg]);
+    }
+
+    /// Merges messages into the cache of a channel. Messages that are already cached are replaced
+    pub fn insert_messages(
+        &mut self,
+        channel_id: Snowflake,
+        msgs: impl IntoIterator<Item = Message>,
+    ) {
+        let cache = self.message_cache.entry(channel_id).or_default();
+
+        for msg in msgs {
+            cache.insert(msg.id, msg);
+        }
+    }
+
+    /// Applies a change to a cached message. Returns false if the message is not cached
+    pub fn update_message(
+        &mut self,
+        channel_id: Snowflake,
+        id: Snowflake,
+        f: impl FnOnce(&mut Message),
+    ) -> bool {
+        if let Some(msg) = self
+            .message_cache
+            .get_mut(&channel_id)
+            .and_then(|msgs| msgs.get_mut(&id))
+        {
+            f(msg);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes a message from the cache and returns it
+    pub fn take_message(&mut self, channel_id: Snowflake, id: Snowflake) -> Option<Message> {
+        self.message_cache.get_mut(&channel_id)?.remove(&id)
+    }
+
+    /// Removes messages from the cache or only marks them as deleted if `keep` is set
+    pub fn delete_messages(&mut self, channel_id: Snowflake, ids: &[Snowflake], keep: bool) {
+        if let Some(msgs) = self.message_cache.get_mut(&channel_id) {
+            for id in ids {
+                if keep {
+                    if let Some(msg) = msgs.get_mut(id) {
+                        msg.deleted = true;
+                    }
+                } else {
+                    msgs.remove(id);
+                }
+            }
+        }
+    }
+
+    /// Replaces the pending message with the given nonce by the message discord created from it
+    pub fn reconcile_message(&mut self, channel_id: Snowflake, nonce: &str, msg: Message) {
+        if let Some(msgs) = self.message_cache.get_mut(&channel_id) {
+            msgs.retain(|_, m| {
+                m.nonce.as_deref() != Some(nonce) || m.status == MessageStatus::Sent
             });
+        }
+
+        self.insert_message(channel_id, msg);
+    }
+
+    pub fn set_message_status(
+        &mut self,
+        channel_id: Snowflake,
+        nonce: &str,
+        new_status: MessageStatus,
+    ) {
+        if let Some(msgs) = self.message_cache.get_mut(&channel_id) {
+            for msg in msgs.values_mut() {
+                if msg.nonce.as_deref() == Some(nonce) && msg.status != MessageStatus::Sent {
+                    msg.status = new_status.clone();
+                }
+            }
+        }
+    }
+
+    /// Marks the loaded messages of an opened channel as read
+    pub fn read_channel(&mut self, channel_id: Snowflake) {
+        let latest = match self
+            .message_cache
+            .get(&channel_id)
+            .and_then(|msgs| msgs.keys().next_back())
+        {
+            Some(latest) => *latest,
+            None => return,
+        };
+
+        if let Some(last_read) = self.read_states.insert(channel_id, latest) {
+            self.unread_dividers.insert(channel_id, last_read);
+        } else {
+            self.unread_dividers.remove(&channel_id);
+        }
+    }
+
+    pub fn start_typing(&mut self, channel_id: Snowflake, user_id: Snowflake) {
+        self.typing
+            .entry(channel_id)
+            .or_default()
+            .insert(user_id, Instant::now());
+    }
+
+    pub fn stop_typing(&mut self, channel_id: Snowflake, user_id: Snowflake) {
+        if let Some(users) = self.typing.get_mut(&channel_id) {
+            users.remove(&user_id);
+        }
+    }
+
+    /// Removes users that stopped typing without sending a message
+    pub fn remove_expired_typing(&mut self) {
+        for users in self.typing.values_mut() {
+            users.retain(|_, started| started.elapsed() < TYPING_DURATION);
+        }
+        self.typing.retain(|_, users| !users.is_empty());
+    }
+
+    /// Other users that are typing in a channel, in the order they started typing
+    pub fn typing_users(&self, channel_id: Snowflake) -> Vec<&User> {
+        let mut users = self
+            .typing
+            .get(&channel_id)
+            .into_iter()
+            .flatten()
+            .filter(|(id, started)| **id != self.user_id && started.elapsed() < TYPING_DURATION)
+            .filter_map(|(id, started)| Some((self.user_cache.get(id)?, started)))
+            .collect::<Vec<_>>();
+        users.sort_by_key(|(_, started)| **started);
+
+        users.into_iter().map(|(user, _)| user).collect()
+    }
+
+    pub fn message_history(&self, channel_id: Snowflake) -> MessageHistory {
+        self.message_history
+            .get(&channel_id)
+            .cloned()
+            .unwrap_or_default()
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Message {
-    Default { user_id: String, content: String },
+/// How much of the message history of a channel has been fetched
+#[derive(Default, Debug, Clone, PartialEq)]
+pub enum MessageHistory {
+    #[default]
+    NotLoaded,
+    Loading,
+    /// There are older messages that have not been fetched yet
+    Partial,
+    Complete,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RelationshipKind {
     None,
     Friend,
@@ -65,9 +385,22 @@ pub enum RelationshipKind {
     Implicit,
 }
 
+impl From<u16> for RelationshipKind {
+    fn from(kind: u16) -> Self {
+        match kind {
+            1 => RelationshipKind::Friend,
+            2 => RelationshipKind::Blocked,
+            3 => RelationshipKind::PendingIncoming,
+            4 => RelationshipKind::PendingOutgoing,
+            5 => RelationshipKind::Implicit,
+            _ => RelationshipKind::None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Relationship {
-    pub id: String,
+    pub id: Snowflake,
     pub kind: RelationshipKind,
 }
 
@@ -79,14 +412,14 @@ pub enum PrivateChannelKind {
 
 #[derive(Debug, Clone)]
 pub struct PrivateChannel {
-    pub id: String,
+    pub id: Snowflake,
     pub kind: PrivateChannelKind,
-    pub recipients: Vec<String>,
-    pub owner_id: Option<String>,
+    pub recipients: Vec<Snowflake>,
+    pub owner_id: Option<Snowflake>,
     pub name: Option<String>,
     pub icon: Option<String>,
     pub icon_handle: Option<image::Handle>,
-    pub last_message_timestamp: u64,
+    pub last_message_id: Option<Snowflake>,
 }
 
 impl PartialEq for PrivateChannel {
@@ -0,0 +1,195 @@
+use once_cell::sync::OnceCell;
+use time::{macros::format_description, OffsetDateTime, UtcOffset};
+
+/// Offset of the local time zone. The time crate can only read it while there is a single thread
+static LOCAL_OFFSET: OnceCell<UtcOffset> = OnceCell::new();
+
+/// Reads the local offset. Has to be called before any threads are started
+pub fn init_local_offset() {
+    LOCAL_OFFSET.get_or_init(|| UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+}
+
+pub fn local(datetime: OffsetDateTime) -> OffsetDateTime {
+    datetime.to_offset(LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC))
+}
+
+/// Styles of timestamps in messages, e.g. `<t:1618953630:R>`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampStyle {
+    /// 4:20 PM
+    ShortTime,
+    /// 4:20:30 PM
+    LongTime,
+    /// 04/20/2021
+    ShortDate,
+    /// April 20, 2021
+    LongDate,
+    /// April 20, 2021 4:20 PM
+    #[default]
+    ShortDateTime,
+    /// Tuesday, April 20, 2021 4:20 PM
+    LongDateTime,
+    /// 2 months ago
+    Relative,
+}
+
+impl TimestampStyle {
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Some(match tag {
+            "t" => TimestampStyle::ShortTime,
+            "T" => TimestampStyle::LongTime,
+            "d" => TimestampStyle::ShortDate,
+            "D" => TimestampStyle::LongDate,
+            "f" => TimestampStyle::ShortDateTime,
+            "F" => TimestampStyle::LongDateTime,
+            "R" => TimestampStyle::Relative,
+            _ => return None,
+        })
+    }
+}
+
+/// Formats a time in the local time zone
+pub fn format(datetime: OffsetDateTime, style: TimestampStyle) -> String {
+    let datetime = local(datetime);
+
+    let formatted = match style {
+        TimestampStyle::ShortTime => {
+            datetime.format(format_description!("[hour repr:12 padding:none]:[minute] [period]"))
+        }
+        TimestampStyle::LongTime => datetime.format(format_description!(
+            "[hour repr:12 padding:none]:[minute]:[second] [period]"
+        )),
+        TimestampStyle::ShortDate => datetime.format(format_description!("[month]/[day]/[year]")),
+        TimestampStyle::LongDate => datetime.format(format_description!(
+            "[month repr:long] [day padding:none], [year]"
+        )),
+        TimestampStyle::ShortDateTime => datetime.format(format_description!(
+            "[month repr:long] [day padding:none], [year] [hour repr:12 padding:none]:[minute] [period]"
+        )),
+        TimestampStyle::LongDateTime => datetime.format(format_description!(
+            "[weekday], [month repr:long] [day padding:none], [year] [hour repr:12 padding:none]:[minute] [period]"
+        )),
+        TimestampStyle::Relative => return relative(datetime, OffsetDateTime::now_utc()),
+    };
+
+    formatted.unwrap_or_default()
+}
+
+/// "in 5 minutes" or "3 days ago", in the largest unit that fits. Times less than
+/// a second away are "now"
+fn relative(datetime: OffsetDateTime, now: OffsetDateTime) -> String {
+    const UNITS: [(i64, &str); 6] = [
+        (365 * 24 * 60 * 60, "year"),
+        (30 * 24 * 60 * 60, "month"),
+        (24 * 60 * 60, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ];
+
+    let seconds = (datetime - now).whole_seconds();
+    let (unit_seconds, unit) = match UNITS
+        .into_iter()
+        .find(|(unit_seconds, _)| seconds.abs() >= *unit_seconds)
+    {
+        Some(unit) => unit,
+        None => return String::from("now"),
+    };
+
+    let count = seconds.abs() / unit_seconds;
+    let plural = if count == 1 { "" } else { "s" };
+
+    if seconds < 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
+
+/// "Today at 4:20 PM", "Yesterday at 4:20 PM" or "04/20/2021 4:20 PM"
+pub fn calendar(datetime: OffsetDateTime) -> String {
+    let date = local(datetime).date();
+    let today = local(OffsetDateTime::now_utc()).date();
+    let time = format(datetime, TimestampStyle::ShortTime);
+
+    if date == today {
+        format!("Today at {time}")
+    } else if today.previous_day() == Some(date) {
+        format!("Yesterday at {time}")
+    } else {
+        format!("{} {time}", format(datetime, TimestampStyle::ShortDate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use super::*;
+
+    // The local offset is not initialized in tests, so times are formatted in UTC
+    const TIME: OffsetDateTime = datetime!(2021-04-20 16:20:30 UTC);
+
+    #[test]
+    fn styles() {
+        let tags = ["t", "T", "d", "D", "f", "F", "R"].map(TimestampStyle::from_tag);
+        assert_eq!(
+            tags,
+            [
+                TimestampStyle::ShortTime,
+                TimestampStyle::LongTime,
+                TimestampStyle::ShortDate,
+                TimestampStyle::LongDate,
+                TimestampStyle::ShortDateTime,
+                TimestampStyle::LongDateTime,
+                TimestampStyle::Relative,
+            ]
+            .map(Some)
+        );
+        assert_eq!(TimestampStyle::from_tag("x"), None);
+    }
+
+    #[test]
+    fn formats() {
+        let formats = [
+            (TimestampStyle::ShortTime, "4:20 PM"),
+            (TimestampStyle::LongTime, "4:20:30 PM"),
+            (TimestampStyle::ShortDate, "04/20/2021"),
+            (TimestampStyle::LongDate, "April 20, 2021"),
+            (TimestampStyle::ShortDateTime, "April 20, 2021 4:20 PM"),
+            (
+                TimestampStyle::LongDateTime,
+                "Tuesday, April 20, 2021 4:20 PM",
+            ),
+        ];
+
+        for (style, formatted) in formats {
+            assert_eq!(format(TIME, style), formatted, "{style:?}");
+        }
+        assert!(format(TIME, TimestampStyle::Relative).ends_with(" years ago"));
+    }
+
+    #[test]
+    fn relative_times() {
+        let from_time = |offset: Duration| relative(TIME + offset, TIME);
+
+        assert_eq!(from_time(Duration::ZERO), "now");
+        assert_eq!(from_time(Duration::milliseconds(-500)), "now");
+        assert_eq!(from_time(Duration::seconds(-1)), "1 second ago");
+        assert_eq!(from_time(Duration::seconds(30)), "in 30 seconds");
+        assert_eq!(from_time(Duration::seconds(-90)), "1 minute ago");
+        assert_eq!(from_time(Duration::hours(2)), "in 2 hours");
+        assert_eq!(from_time(Duration::days(-3)), "3 days ago");
+        assert_eq!(from_time(Duration::days(-61)), "2 months ago");
+        assert_eq!(from_time(Duration::days(400)), "in 1 year");
+    }
+
+    #[test]
+    fn calendar_days() {
+        let now = OffsetDateTime::now_utc();
+
+        assert!(calendar(now).starts_with("Today at "));
+        assert!(calendar(now - Duration::days(1)).starts_with("Yesterday at "));
+        assert_eq!(calendar(TIME), "04/20/2021 4:20 PM");
+    }
+}
@@ -1,15 +1,17 @@
-use anyhow::Result;
+use std::fmt;
+
 use iced_native::image;
 use serde::{
     de::{self},
-    Deserialize, Deserializer,
+    Deserialize, Deserializer, Serialize,
 };
+use time::OffsetDateTime;
 
-use crate::api::rest_client::REST_BASE_URL;
+use super::snowflake::Snowflake;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct User {
-    pub id: String,
+    pub id: Snowflake,
     pub username: String,
     #[serde(deserialize_with = "str_to_u16")]
     pub discriminator: u16,
@@ -19,6 +21,10 @@ pub struct User {
     pub avatar_handle: Option<image::Handle>,
     #[serde(skip)]
     pub presence: Presence,
+    #[serde(skip)]
+    pub client_status: ClientStatus,
+    #[serde(skip)]
+    pub activities: Vec<Activity>,
 }
 
 fn str_to_u16<'a, D: Deserializer<'a>>(deserializer: D) -> Result<u16, D::Error> {
@@ -28,16 +34,25 @@ fn str_to_u16<'a, D: Deserializer<'a>>(deserializer: D) -> Result<u16, D::Error>
 }
 
 impl User {
-    pub async fn from_token(token: String) -> Result<Self> {
-        let data = reqwest::Client::new()
-            .get(format!("{REST_BASE_URL}/users/@me"))
-            .header("Authorization", token)
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        Ok(serde_json::from_str::<Self>(&data)?)
+    /// Text of the custom status, prefixed with its emoji if it is a unicode emoji
+    pub fn custom_status(&self) -> Option<String> {
+        let activity = self
+            .activities
+            .iter()
+            .find(|a| a.kind == ActivityKind::Custom)?;
+
+        let emoji = activity
+            .emoji
+            .as_ref()
+            .filter(|e| e.id.is_none())
+            .map(|e| e.name.as_str());
+
+        match (emoji, activity.state.as_deref()) {
+            (Some(emoji), Some(state)) => Some(format!("{emoji} {state}")),
+            (Some(emoji), None) => Some(emoji.to_string()),
+            (None, Some(state)) => Some(state.to_string()),
+            (None, None) => None,
+        }
     }
 }
 
@@ -47,11 +62,101 @@ impl PartialEq for User {
     }
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Presence {
+    /// Invisible users appear offline. Setting the own presence to offline makes the user invisible
     #[default]
+    #[serde(other, rename(serialize = "invisible"))]
     Offline,
     Online,
     Idle,
+    #[serde(rename = "dnd")]
     DoNotDisturb,
 }
+
+impl fmt::Display for Presence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Presence::Offline => "Offline",
+            Presence::Online => "Online",
+            Presence::Idle => "Idle",
+            Presence::DoNotDisturb => "Do Not Disturb",
+        })
+    }
+}
+
+/// Custom status of the current user as it is stored in its settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomStatus {
+    pub text: String,
+    /// When discord clears the status, `None` if it is kept until it is changed
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl CustomStatus {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= OffsetDateTime::now_utc())
+    }
+}
+
+/// Status of the user on each platform it is connected from
+#[derive(Default, Debug, Clone, Deserialize)]
+pub struct ClientStatus {
+    pub desktop: Option<Presence>,
+    pub mobile: Option<Presence>,
+    pub web: Option<Presence>,
+}
+
+impl ClientStatus {
+    /// Whether the user is only connected from a phone
+    pub fn mobile_only(&self) -> bool {
+        self.mobile.is_some() && self.desktop.is_none() && self.web.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "u8")]
+pub enum ActivityKind {
+    Playing,
+    Streaming,
+    Listening,
+    Watching,
+    Custom,
+    Competing,
+    Unknown(u8),
+}
+
+impl From<u8> for ActivityKind {
+    fn from(kind: u8) -> Self {
+        match kind {
+            0 => ActivityKind::Playing,
+            1 => ActivityKind::Streaming,
+            2 => ActivityKind::Listening,
+            3 => ActivityKind::Watching,
+            4 => ActivityKind::Custom,
+            5 => ActivityKind::Competing,
+            kind => ActivityKind::Unknown(kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Activity {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ActivityKind,
+    /// Text of a custom status or the party status of a game
+    pub state: Option<String>,
+    pub details: Option<String>,
+    pub emoji: Option<ActivityEmoji>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActivityEmoji {
+    pub name: String,
+    pub id: Option<Snowflake>,
+    #[serde(default)]
+    pub animated: bool,
+}
@@ -0,0 +1,109 @@
+use iced_native::{
+    layout, renderer,
+    widget::{Tree, Widget},
+    Element, Layout, Length, Point, Rectangle, Size,
+};
+
+/// Draws a badge on top of the bottom right corner of its content
+pub fn badge<'a, Message, Renderer>(
+    content: impl Into<Element<'a, Message, Renderer>>,
+    badge: impl Into<Element<'a, Message, Renderer>>,
+) -> Badge<'a, Message, Renderer> {
+    Badge {
+        content: content.into(),
+        badge: badge.into(),
+    }
+}
+
+pub struct Badge<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    badge: Element<'a, Message, Renderer>,
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Badge<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content), Tree::new(&self.badge)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&[&self.content, &self.badge]);
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let content = self.content.as_widget().layout(renderer, limits);
+        let size = content.size();
+
+        let mut badge = self
+            .badge
+            .as_widget()
+            .layout(renderer, &layout::Limits::new(Size::ZERO, size));
+        let badge_size = badge.size();
+        badge.move_to(Point::new(
+            size.width - badge_size.width,
+            size.height - badge_size.height,
+        ));
+
+        layout::Node::with_children(size, vec![content, badge])
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        let mut children = layout.children();
+        let (content_layout, badge_layout) = match (children.next(), children.next()) {
+            (Some(content), Some(badge)) => (content, badge),
+            _ => return,
+        };
+
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            content_layout,
+            cursor_position,
+            viewport,
+        );
+
+        // Images are drawn above quads of the same layer, so the badge needs its own layer
+        renderer.with_layer(badge_layout.bounds(), |renderer| {
+            self.badge.as_widget().draw(
+                &tree.children[1],
+                renderer,
+                theme,
+                style,
+                badge_layout,
+                cursor_position,
+                viewport,
+            );
+        });
+    }
+}
+
+impl<'a, Message, Renderer> From<Badge<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(badge: Badge<'a, Message, Renderer>) -> Self {
+        Element::new(badge)
+    }
+}
@@ -1,53 +1,81 @@
 use iced::widget::scrollable::Properties;
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, container, horizontal_rule, scrollable, svg, vertical_space},
-    Element, Length,
+    widget::{button, column, container, horizontal_rule, scrollable, svg, Column},
+    Alignment, Element, Length,
 };
 use iced_graphics::Renderer;
 use iced_lazy::Component;
 
-use crate::gui::{
-    icons,
-    theme::{Button, Container, Rule, Scrollable, Theme},
+use crate::{
+    data::{guild::Guild, snowflake::Snowflake, user::User},
+    gui::{
+        icons,
+        theme::{Button, Container, Rule, Scrollable, Theme},
+    },
+};
+
+use super::{
+    empty,
+    images::{guild_icon, user_avatar},
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum View {
     DirectMessages,
+    Guild(Snowflake),
+    Status,
     Settings,
 }
 
-pub fn guildbar<Message>(
+pub fn guildbar<'a, Message>(
     active_view: View,
+    guilds: &'a [Guild],
+    user: Option<&'a User>,
     on_select: impl Fn(View) -> Message + 'static,
-) -> Guildbar<Message> {
-    Guildbar::new(active_view, on_select)
+) -> Guildbar<'a, Message> {
+    Guildbar::new(active_view, guilds, user, on_select)
 }
 
 #[derive(Debug, Clone)]
 pub enum GuildbarEvent {
     DirectMessagesPressed,
+    GuildPressed(Snowflake),
+    StatusPressed,
     SettingsPressed,
 }
 
-pub struct Guildbar<Message> {
+pub struct Guildbar<'a, Message> {
     active_view: View,
+    guilds: &'a [Guild],
+    /// Current user, `None` while disconnected
+    user: Option<&'a User>,
     on_select: Box<dyn Fn(View) -> Message>,
 }
 
-impl<Message> Guildbar<Message> {
-    fn new(active_view: View, on_select: impl Fn(View) -> Message + 'static) -> Self {
+impl<'a, Message> Guildbar<'a, Message> {
+    fn new(
+        active_view: View,
+        guilds: &'a [Guild],
+        user: Option<&'a User>,
+        on_select: impl Fn(View) -> Message + 'static,
+    ) -> Self {
         Self {
             active_view,
+            guilds,
+            user,
             on_select: Box::new(on_select),
         }
     }
 }
 
-impl<Message, Backend> Component<Message, Renderer<Backend, Theme>> for Guildbar<Message>
+impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>> for Guildbar<'a, Message>
 where
-    Backend: iced_graphics::Backend + iced_graphics::backend::Svg + 'static,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
 {
     type State = ();
     type Event = GuildbarEvent;
@@ -55,6 +83,8 @@ where
     fn update(&mut self, _state: &mut Self::State, event: Self::Event) -> Option<Message> {
         match event {
             GuildbarEvent::DirectMessagesPressed => Some((self.on_select)(View::DirectMessages)),
+            GuildbarEvent::GuildPressed(id) => Some((self.on_select)(View::Guild(id))),
+            GuildbarEvent::StatusPressed => Some((self.on_select)(View::Status)),
             GuildbarEvent::SettingsPressed => Some((self.on_select)(View::Settings)),
         }
     }
@@ -67,11 +97,31 @@ where
             .padding(15)
             .on_press(GuildbarEvent::DirectMessagesPressed);
 
+        // Unavailable guilds are hidden until they are sent again
+        let guild_buttons = self
+            .guilds
+            .iter()
+            .filter(|g| !g.unavailable)
+            .map(|g| {
+                button(guild_icon(g, 45))
+                    .style(Button::Border(
+                        self.active_view == View::Guild(g.id),
+                        Some(25.5),
+                        2.0,
+                    ))
+                    .width(Length::Units(51))
+                    .height(Length::Units(51))
+                    .padding(3)
+                    .on_press(GuildbarEvent::GuildPressed(g.id))
+                    .into()
+            })
+            .collect();
+
         let guilds = scrollable(
             column![
                 private_channels_button,
                 horizontal_rule(2).style(Rule::Width(2, 60.0)),
-                vertical_space(Length::Units(750))
+                Column::with_children(guild_buttons).spacing(10)
             ]
             .spacing(10),
         )
@@ -89,13 +139,32 @@ where
             .padding(6)
             .on_press(GuildbarEvent::SettingsPressed);
 
+        // The status picker is opened with the avatar of the current user
+        let status_button: Element<_, _> = match self.user {
+            Some(user) => button(user_avatar(user, 31, true))
+                .style(Button::TransparentHover(
+                    self.active_view == View::Status,
+                    Some(17.5),
+                ))
+                .width(Length::Units(35))
+                .height(Length::Units(35))
+                .padding(2)
+                .on_press(GuildbarEvent::StatusPressed)
+                .into(),
+            None => empty().into(),
+        };
+
         container(
             column![
                 guilds,
                 horizontal_rule(2).style(Rule::Width(2, 60.0)),
-                container(settings_button)
-                    .width(Length::Fill)
-                    .align_x(Horizontal::Center)
+                container(
+                    column![status_button, settings_button]
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                )
+                .width(Length::Fill)
+                .align_x(Horizontal::Center)
             ]
             .spacing(10),
         )
@@ -107,13 +176,17 @@ where
     }
 }
 
-impl<'a, Message, Backend> From<Guildbar<Message>>
+impl<'a, Message, Backend> From<Guildbar<'a, Message>>
     for Element<'a, Message, Renderer<Backend, Theme>>
 where
     Message: 'a,
-    Backend: iced_graphics::Backend + iced_graphics::backend::Svg + 'static,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
 {
-    fn from(guildbar: Guildbar<Message>) -> Self {
+    fn from(guildbar: Guildbar<'a, Message>) -> Self {
         iced_lazy::component(guildbar)
     }
 }
@@ -0,0 +1,172 @@
+use iced_native::{
+    event, keyboard, layout,
+    mouse::{self, ScrollDelta},
+    overlay, renderer,
+    widget::{
+        tree::{self, Tree},
+        Widget,
+    },
+    Clipboard, Element, Event, Layout, Length, Point, Rectangle, Shell,
+};
+
+/// Only passes horizontal wheel movement to its content, vertical movement is passed while
+/// shift is held. A horizontal scrollable inside of a vertical list would otherwise capture
+/// the wheel and stop the list from scrolling
+pub fn horizontal_wheel<'a, Message, Renderer>(
+    content: impl Into<Element<'a, Message, Renderer>>,
+) -> HorizontalWheel<'a, Message, Renderer> {
+    HorizontalWheel {
+        content: content.into(),
+    }
+}
+
+pub struct HorizontalWheel<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+}
+
+/// Turns vertical movement into horizontal movement if shift is held and drops it otherwise
+fn horizontal(delta: ScrollDelta, shift: bool) -> Option<ScrollDelta> {
+    let (x, y) = match delta {
+        ScrollDelta::Lines { x, y } | ScrollDelta::Pixels { x, y } => (x, y),
+    };
+    let x = match (shift, y != 0.0) {
+        (true, true) => y,
+        (false, true) => return None,
+        (_, false) => x,
+    };
+
+    Some(match delta {
+        ScrollDelta::Lines { .. } => ScrollDelta::Lines { x, y: 0.0 },
+        ScrollDelta::Pixels { .. } => ScrollDelta::Pixels { x, y: 0.0 },
+    })
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for HorizontalWheel<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<keyboard::Modifiers>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(keyboard::Modifiers::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let modifiers = tree.state.downcast_mut::<keyboard::Modifiers>();
+
+        let event = match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(changed)) => {
+                *modifiers = changed;
+                event
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                match horizontal(delta, modifiers.shift()) {
+                    Some(delta) => Event::Mouse(mouse::Event::WheelScrolled { delta }),
+                    None => return event::Status::Ignored,
+                }
+            }
+            event => event,
+        };
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<HorizontalWheel<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(horizontal_wheel: HorizontalWheel<'a, Message, Renderer>) -> Self {
+        Element::new(horizontal_wheel)
+    }
+}
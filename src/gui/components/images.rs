@@ -1,25 +1,30 @@
 use iced::{
     color,
-    widget::{container, image, svg},
+    widget::{container, image, svg, text},
     Color, Element, Length,
 };
 use iced_graphics::Renderer;
 
 use crate::{
-    data::user::User,
+    data::{
+        guild::Guild,
+        user::{Presence, User},
+    },
     gui::{
         icons,
         theme::{Container, Theme},
     },
 };
 
-use super::empty;
+use super::{badge::badge, empty};
 
 pub const DEFAULT_ACCENT_COLOR: u32 = 5793266;
 
+/// Avatar of a user with a badge showing its presence if `status` is set
 pub fn user_avatar<'a, Message, Backend>(
     user: &User,
     size: u16,
+    status: bool,
 ) -> Element<'a, Message, Renderer<Backend, Theme>>
 where
     Message: 'a,
@@ -34,11 +39,31 @@ where
         empty().into()
     };
 
-    container(image)
+    let avatar = container(image)
         .style(Container::Color(Color::TRANSPARENT, size as f32 / 2.0))
         .width(Length::Units(size))
-        .height(Length::Units(size))
-        .into()
+        .height(Length::Units(size));
+
+    if !status {
+        return avatar.into();
+    }
+
+    // Online users that are only connected from a phone get an upright badge
+    let badge_size = (size as f32 * 0.4).round().max(8.0) as u16;
+    let badge_height = if user.presence == Presence::Online && user.client_status.mobile_only() {
+        (badge_size as f32 * 1.4).round() as u16
+    } else {
+        badge_size
+    };
+
+    badge(
+        avatar,
+        container(empty())
+            .style(Container::Status(user.presence, badge_size as f32 / 2.0))
+            .width(Length::Units(badge_size))
+            .height(Length::Units(badge_height)),
+    )
+    .into()
 }
 
 pub fn channel_icon<'a, Message, Backend>(
@@ -76,3 +101,47 @@ where
         .height(Length::Units(size))
         .into()
 }
+
+pub fn guild_icon<'a, Message, Backend>(
+    guild: &Guild,
+    size: u16,
+) -> Element<'a, Message, Renderer<Backend, Theme>>
+where
+    Message: 'a,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    if let Some(handle) = &guild.icon_handle {
+        container(image(handle.clone()))
+            .style(Container::Color(Color::TRANSPARENT, size as f32 / 2.0))
+            .width(Length::Units(size))
+            .height(Length::Units(size))
+            .into()
+    } else {
+        // Guilds without an icon are shown with the first letter of every word in their name
+        let acronym = acronym(&guild.name);
+        let text_size = if acronym.chars().count() > 2 {
+            size / 4
+        } else {
+            size / 3
+        };
+
+        container(text(acronym).size(text_size))
+            .style(Container::BackgroundWeak(size as f32 / 2.0))
+            .width(Length::Units(size))
+            .height(Length::Units(size))
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
+
+fn acronym(name: &str) -> String {
+    name.replace("'s ", " ")
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(5)
+        .collect()
+}
@@ -0,0 +1,184 @@
+use iced_native::{
+    event,
+    keyboard::{self, KeyCode, Modifiers},
+    layout, mouse, overlay, renderer,
+    widget::{
+        operation::{Focusable, Operation},
+        Id, Tree, Widget,
+    },
+    Clipboard, Element, Event, Layout, Length, Point, Rectangle, Shell,
+};
+
+type OnKeyPress<'a, Message> = dyn Fn(KeyCode, Modifiers, Option<&Id>) -> Option<Message> + 'a;
+
+/// Publishes a message for key presses that its content did not capture
+/// (e.g. arrow up or escape in a text input). The id of the focused widget
+/// in its content is passed along, if it has one
+pub fn key_listener<'a, Message, Renderer>(
+    content: impl Into<Element<'a, Message, Renderer>>,
+    on_key_press: impl Fn(KeyCode, Modifiers, Option<&Id>) -> Option<Message> + 'a,
+) -> KeyListener<'a, Message, Renderer> {
+    KeyListener {
+        content: content.into(),
+        on_key_press: Box::new(on_key_press),
+    }
+}
+
+pub struct KeyListener<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    on_key_press: Box<OnKeyPress<'a, Message>>,
+}
+
+/// Finds the id of the focused widget
+#[derive(Default)]
+struct FindFocused(Option<Id>);
+
+impl<T> Operation<T> for FindFocused {
+    fn container(
+        &mut self,
+        _id: Option<&Id>,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation<T>),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn focusable(&mut self, state: &mut dyn Focusable, id: Option<&Id>) {
+        if state.is_focused() {
+            self.0 = id.cloned();
+        }
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for KeyListener<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let status = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        );
+
+        // Text inputs capture escape to lose focus, so it is always forwarded
+        if let Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code,
+            modifiers,
+        }) = event
+        {
+            if status == event::Status::Ignored || key_code == KeyCode::Escape {
+                let mut focused = FindFocused::default();
+                self.content
+                    .as_widget()
+                    .operate(&mut tree.children[0], layout, &mut focused);
+
+                if let Some(message) = (self.on_key_press)(key_code, modifiers, focused.0.as_ref())
+                {
+                    shell.publish(message);
+                    return event::Status::Captured;
+                }
+            }
+        }
+
+        status
+    }
+
+    fn operate(&self, tree: &mut Tree, layout: Layout<'_>, operation: &mut dyn Operation<Message>) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, operation);
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<KeyListener<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(key_listener: KeyListener<'a, Message, Renderer>) -> Self {
+        Element::new(key_listener)
+    }
+}
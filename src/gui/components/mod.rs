@@ -1,9 +1,15 @@
 use iced::{widget, Length};
 
+pub mod badge;
 pub mod guildbar;
+pub mod horizontal_wheel;
 pub mod images;
+pub mod key_listener;
 pub mod sidebar;
 pub mod text_chat;
+pub mod text_lines;
+pub mod virtual_list;
+pub mod wrap;
 
 pub fn empty() -> widget::Space {
     widget::Space::new(Length::Shrink, Length::Shrink)
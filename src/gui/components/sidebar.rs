@@ -1,16 +1,18 @@
 use iced::widget::scrollable::Properties;
 use iced::{
     alignment::Vertical,
-    widget::{button, container, horizontal_rule, scrollable, text, Column},
-    Element, Length,
+    widget::{button, container, horizontal_rule, scrollable, svg, text, Column},
+    Alignment, Element, Length,
 };
 use iced_graphics::Renderer;
 use iced_lazy::Component;
-use iced_native::row;
+use iced_native::{column, row};
 
+use crate::data::guild::{GuildChannel, GuildChannelKind};
 use crate::data::state::{PrivateChannel, PrivateChannelKind};
 use crate::data::user::User;
-use crate::gui::theme::{Button, Container, Theme};
+use crate::gui::icons;
+use crate::gui::theme::{Button, Container, Svg, Text, Theme};
 
 use super::images::{channel_icon, user_avatar};
 
@@ -18,9 +20,26 @@ use super::images::{channel_icon, user_avatar};
 pub enum SidebarEntryType<T: Clone + PartialEq> {
     Button(T, String),
     PrivateChannel(PrivateChannel, Option<User>),
+    /// category channel, collapsed
+    Category(GuildChannel, bool),
+    GuildChannel(GuildChannel),
     Spacer,
 }
 
+impl<T: Clone + PartialEq> SidebarEntryType<T> {
+    /// Categories and channels without a text chat can be pressed but are never selected
+    pub fn is_selectable(&self) -> bool {
+        match self {
+            SidebarEntryType::Category(..) | SidebarEntryType::Spacer => false,
+            SidebarEntryType::GuildChannel(channel) => matches!(
+                channel.kind,
+                GuildChannelKind::Text | GuildChannelKind::Announcement
+            ),
+            _ => true,
+        }
+    }
+}
+
 fn sidebar_entry<'a, T, Backend>(
     selected: bool,
     entry_type: &SidebarEntryType<T>,
@@ -41,10 +60,16 @@ where
             match channel.kind {
                 PrivateChannelKind::DirectMessage => {
                     if let Some(user) = user {
-                        row![
-                            user_avatar(user, 25),
-                            text(format!("{:?} {}", user.presence, user.username))
-                        ]
+                        let name: Element<_, _> = match user.custom_status() {
+                            Some(status) => column![
+                                text(&user.username),
+                                text(status).size(13).style(Text::Weak)
+                            ]
+                            .into(),
+                            None => text(&user.username).into(),
+                        };
+
+                        row![user_avatar(user, 25, true), name].align_items(Alignment::Center)
                     } else {
                         row![text("Error finding user")]
                     }
@@ -60,8 +85,55 @@ where
             }
             .spacing(10),
         )
-        .height(Length::Units(25))
+        .height(Length::Units(35))
         .align_y(Vertical::Center),
+        SidebarEntryType::Category(category, collapsed) => {
+            let chevron = if *collapsed {
+                icons::CHEVRON_RIGHT.clone()
+            } else {
+                icons::CHEVRON_DOWN.clone()
+            };
+
+            return button(
+                row![
+                    svg(chevron)
+                        .style(Svg::Weak)
+                        .width(Length::Units(12))
+                        .height(Length::Units(12)),
+                    text(category.name.to_uppercase())
+                        .size(14)
+                        .style(Text::Weak)
+                ]
+                .spacing(5),
+            )
+            .style(Button::TransparentHover(false, Some(5.0)))
+            .width(Length::Fill)
+            .padding([10, 5, 0, 5])
+            .on_press(entry_type.clone())
+            .into();
+        }
+        SidebarEntryType::GuildChannel(channel) => {
+            let icon = match channel.kind {
+                GuildChannelKind::Announcement => icons::ANNOUNCEMENT.clone(),
+                GuildChannelKind::Voice => icons::VOICE.clone(),
+                GuildChannelKind::Stage => icons::STAGE.clone(),
+                GuildChannelKind::Forum => icons::FORUM.clone(),
+                _ => icons::HASH.clone(),
+            };
+
+            container(
+                row![
+                    svg(icon)
+                        .style(Svg::Weak)
+                        .width(Length::Units(18))
+                        .height(Length::Units(18)),
+                    text(&channel.name)
+                ]
+                .spacing(8),
+            )
+            .height(Length::Units(20))
+            .align_y(Vertical::Center)
+        }
         SidebarEntryType::Spacer => return horizontal_rule(15).into(),
     };
 
@@ -92,6 +164,8 @@ impl<T: Clone + PartialEq> Default for State<T> {
 
 pub struct Sidebar<T: Clone + PartialEq, Message> {
     entries: Vec<SidebarEntryType<T>>,
+    /// Set if the selection is controlled by the parent instead of the last pressed entry
+    selected: Option<Option<SidebarEntryType<T>>>,
     on_select: Box<dyn Fn(SidebarEntryType<T>) -> Message>,
 }
 
@@ -102,9 +176,15 @@ impl<T: Clone + PartialEq, Message> Sidebar<T, Message> {
     ) -> Self {
         Self {
             entries: entries.to_vec(),
+            selected: None,
             on_select: Box::new(on_select),
         }
     }
+
+    pub fn selected(mut self, entry: Option<SidebarEntryType<T>>) -> Self {
+        self.selected = Some(entry);
+        self
+    }
 }
 
 impl<T, Message, Backend> Component<Message, Renderer<Backend, Theme>> for Sidebar<T, Message>
@@ -120,26 +200,25 @@ where
     type Event = SidebarEntryType<T>;
 
     fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
-        state.active_entry = Some(event.clone());
+        if event.is_selectable() {
+            state.active_entry = Some(event.clone());
+        }
         Some((self.on_select)(event))
     }
 
     fn view(&self, state: &Self::State) -> Element<'_, Self::Event, Renderer<Backend, Theme>> {
+        let active_entry = match &self.selected {
+            Some(selected) => selected.as_ref(),
+            None => state
+                .active_entry
+                .as_ref()
+                .or_else(|| self.entries.iter().find(|e| e.is_selectable())),
+        };
+
         let entries = self
             .entries
             .iter()
-            .map(|entry| {
-                let selected = if let Some(active_entry) = &state.active_entry {
-                    *active_entry == *entry
-                } else {
-                    if let Some(first) = self.entries.first() {
-                        *first == *entry
-                    } else {
-                        false
-                    }
-                };
-                sidebar_entry(selected, entry).into()
-            })
+            .map(|entry| sidebar_entry(active_entry == Some(entry), entry).into())
             .collect();
 
         let scrollable = scrollable(
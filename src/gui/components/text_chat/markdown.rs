@@ -0,0 +1,511 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    mem,
+};
+
+use iced::{
+    widget::{
+        button, column, container, horizontal_space, image, scrollable, text, vertical_space,
+        Column, Row,
+    },
+    Alignment, Color, Element, Font, Length,
+};
+use iced_graphics::Renderer;
+use iced_native::row;
+use time::OffsetDateTime;
+
+use crate::{
+    data::{
+        highlight::highlight,
+        markdown::{self, Block, Inline},
+        message::Message,
+        snowflake::Snowflake,
+        state::State,
+        timestamp,
+    },
+    gui::{
+        components::{horizontal_wheel::horizontal_wheel, text_lines::text_lines, wrap::wrap},
+        fonts::{BOLD, BOLD_ITALIC, ITALIC, MONOSPACE},
+        theme::{Button, Container, Scrollable, Text, Theme},
+    },
+};
+
+use super::TextChatEvent;
+
+/// Text sizes of the heading levels
+const HEADING_SIZES: [u16; 3] = [28, 24, 22];
+const TEXT_SIZE: u16 = 20;
+/// The monospace font is wider, so code is a bit smaller than text
+const CODE_SIZE: u16 = 16;
+/// Indent of each list level
+const LIST_INDENT: u16 = 16;
+
+/// Parsed markdown of each message and the content it was parsed from,
+/// so messages are only parsed again after they were edited
+#[derive(Default)]
+pub struct MarkdownCache(RefCell<HashMap<Snowflake, (String, Vec<Block>)>>);
+
+impl MarkdownCache {
+    /// Drops the markdown of messages that are not in the open channel anymore,
+    /// were deleted or were edited since they were parsed
+    pub fn evict(&self, messages: Option<&BTreeMap<Snowflake, Message>>) {
+        self.0.borrow_mut().retain(|id, (parsed, _)| {
+            messages
+                .and_then(|messages| messages.get(id))
+                .map_or(false, |message| {
+                    !message.deleted && message.content == *parsed
+                })
+        });
+    }
+}
+
+/// What is needed to render the markdown of a message
+struct Context<'s> {
+    message_id: Snowflake,
+    channel_id: Snowflake,
+    state: &'s State,
+    revealed_spoilers: &'s HashSet<(Snowflake, usize)>,
+}
+
+impl<'s> Context<'s> {
+    fn revealed(&self, index: usize) -> bool {
+        self.revealed_spoilers.contains(&(self.message_id, index))
+    }
+
+    /// Text of a mention or timestamp and the color of the mentioned role
+    fn tag(&self, inline: &Inline) -> Option<(String, Option<Color>)> {
+        Some(match inline {
+            Inline::UserMention(id) => {
+                let name = self
+                    .state
+                    .display_name(self.channel_id, *id)
+                    .unwrap_or("unknown-user");
+                (format!("@{name}"), None)
+            }
+            Inline::RoleMention(id) => match self.state.role(*id) {
+                Some(role) => {
+                    // Roles without a color have the color 0
+                    let color = (role.color != 0).then(|| {
+                        let [_, r, g, b] = role.color.to_be_bytes();
+                        Color::from_rgb8(r, g, b)
+                    });
+                    (format!("@{}", role.name), color)
+                }
+                None => (String::from("@deleted-role"), None),
+            },
+            Inline::ChannelMention(id) => {
+                let name = self.state.channel_name(*id).unwrap_or("unknown");
+                (format!("#{name}"), None)
+            }
+            Inline::Timestamp(unix, style) => {
+                let datetime = OffsetDateTime::from_unix_timestamp(*unix).ok()?;
+                (timestamp::format(datetime, *style), None)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Text of inlines without their formatting
+    fn plain_text(&self, inlines: &[Inline]) -> String {
+        inlines
+            .iter()
+            .map(|inline| match inline {
+                Inline::Text(content) | Inline::Code(content) => content.clone(),
+                Inline::Bold(inlines)
+                | Inline::Italic(inlines)
+                | Inline::Underline(inlines)
+                | Inline::Strikethrough(inlines)
+                | Inline::Spoiler(_, inlines) => self.plain_text(inlines),
+                Inline::CustomEmoji { name, .. } => format!(":{name}:"),
+                inline => self.tag(inline).map(|(tag, _)| tag).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Formatting of text. Nested styles are combined
+#[derive(Default, Clone, Copy, PartialEq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strikethrough: bool,
+}
+
+impl Style {
+    fn font(self) -> Font {
+        match (self.bold, self.italic) {
+            (false, false) => Font::Default,
+            (true, false) => BOLD,
+            (false, true) => ITALIC,
+            (true, true) => BOLD_ITALIC,
+        }
+    }
+
+    /// Lines are drawn below single lines of text, so such text can not wrap by itself
+    fn has_lines(self) -> bool {
+        self.underline || self.strikethrough
+    }
+}
+
+fn styled_text<'a, Backend>(
+    content: String,
+    style: Style,
+    size: u16,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
+{
+    let content = text(content).font(style.font()).size(size);
+
+    if style.has_lines() {
+        text_lines(content)
+            .underline(style.underline)
+            .strikethrough(style.strikethrough)
+            .into()
+    } else {
+        content.into()
+    }
+}
+
+/// Part of a line that is drawn as one widget
+enum Span {
+    Text(String, Style),
+    Code(String),
+    /// Resolved mention, role color
+    Mention(String, Option<Color>),
+    Timestamp(String),
+    /// image if it is loaded, name
+    Emoji(Option<image::Handle>, String),
+    /// spoiler index, hidden text
+    HiddenSpoiler(usize, String),
+    RevealedSpoiler(Vec<Span>),
+}
+
+/// Flattens inlines into spans. Neighbouring text with the same style is merged,
+/// so plain lines can wrap
+fn spans(context: &Context, inlines: &[Inline], style: Style, spans: &mut Vec<Span>) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(content) => match spans.last_mut() {
+                Some(Span::Text(previous, previous_style)) if *previous_style == style => {
+                    previous.push_str(content)
+                }
+                _ => spans.push(Span::Text(content.clone(), style)),
+            },
+            Inline::Bold(inlines) => {
+                let style = Style {
+                    bold: true,
+                    ..style
+                };
+                self::spans(context, inlines, style, spans)
+            }
+            Inline::Italic(inlines) => {
+                let style = Style {
+                    italic: true,
+                    ..style
+                };
+                self::spans(context, inlines, style, spans)
+            }
+            Inline::Underline(inlines) => {
+                let style = Style {
+                    underline: true,
+                    ..style
+                };
+                self::spans(context, inlines, style, spans)
+            }
+            Inline::Strikethrough(inlines) => {
+                let style = Style {
+                    strikethrough: true,
+                    ..style
+                };
+                self::spans(context, inlines, style, spans)
+            }
+            Inline::Code(content) => spans.push(Span::Code(content.clone())),
+            Inline::Spoiler(index, inlines) if context.revealed(*index) => {
+                let mut revealed_spans = vec![];
+                self::spans(context, inlines, style, &mut revealed_spans);
+                spans.push(Span::RevealedSpoiler(revealed_spans));
+            }
+            Inline::Spoiler(index, inlines) => {
+                spans.push(Span::HiddenSpoiler(*index, context.plain_text(inlines)))
+            }
+            Inline::CustomEmoji { name, id, .. } => {
+                let handle = context.state.emojis.get(id).cloned().flatten();
+                spans.push(Span::Emoji(handle, name.clone()));
+            }
+            inline @ Inline::Timestamp(..) => {
+                if let Some((content, _)) = context.tag(inline) {
+                    spans.push(Span::Timestamp(content));
+                }
+            }
+            inline => {
+                if let Some((content, color)) = context.tag(inline) {
+                    spans.push(Span::Mention(content, color));
+                }
+            }
+        }
+    }
+}
+
+fn span<'a, Backend>(
+    span: Span,
+    message_id: Snowflake,
+    size: u16,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    match span {
+        Span::Text(content, style) => styled_text(content, style, size),
+        Span::Code(content) => container(text(content).font(MONOSPACE).size(size - 2))
+            .style(Container::Code(3.0))
+            .padding([0, 3])
+            .into(),
+        Span::Mention(content, color) => container(text(content).size(size).style(match color {
+            Some(color) => Text::Color(color),
+            None => Text::Mention,
+        }))
+        .style(Container::Mention(color))
+        .padding([0, 3])
+        .into(),
+        Span::Timestamp(content) => container(text(content).size(size))
+            .style(Container::Code(3.0))
+            .padding([0, 3])
+            .into(),
+        // Custom emojis are shown by name until their image is loaded
+        Span::Emoji(Some(handle), _) => image(handle)
+            .width(Length::Units(size + 2))
+            .height(Length::Units(size + 2))
+            .into(),
+        Span::Emoji(None, name) => text(format!(":{name}:"))
+            .size(size)
+            .style(Text::Weak)
+            .into(),
+        // The text keeps the size of the spoiler but can not be seen
+        Span::HiddenSpoiler(index, content) => button(
+            container(
+                text(content)
+                    .size(size)
+                    .style(Text::Color(Color::TRANSPARENT)),
+            )
+            .style(Container::Spoiler(false))
+            .padding([0, 3]),
+        )
+        .style(Button::TransparentHover(false, Some(3.0)))
+        .padding(0)
+        .on_press(TextChatEvent::SpoilerPressed(message_id, index))
+        .into(),
+        Span::RevealedSpoiler(spans) => container(Row::with_children(
+            spans
+                .into_iter()
+                .map(|s| self::span(s, message_id, size))
+                .collect(),
+        ))
+        .style(Container::Spoiler(true))
+        .padding([0, 3])
+        .into(),
+    }
+}
+
+fn line<'a, Backend>(
+    context: &Context,
+    inlines: &[Inline],
+    style: Style,
+    size: u16,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    let mut line_spans = vec![];
+    spans(context, inlines, style, &mut line_spans);
+
+    // A single text widget wraps long lines, a row of spans can not
+    if let [Span::Text(content, style)] = line_spans.as_mut_slice() {
+        if !style.has_lines() {
+            return styled_text(mem::take(content), *style, size);
+        }
+    }
+
+    // Otherwise text is split into words, so the line can wrap between them
+    let mut children = vec![];
+    for s in line_spans {
+        match s {
+            Span::Text(content, style) => children.extend(
+                content
+                    .split_inclusive(' ')
+                    .map(|word| styled_text(word.to_string(), style, size)),
+            ),
+            s => children.push(span(s, context.message_id, size)),
+        }
+    }
+
+    wrap(children).into()
+}
+
+fn block<'a, Backend>(
+    context: &Context,
+    block: &Block,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    match block {
+        Block::Paragraph(inlines) if inlines.is_empty() => {
+            vertical_space(Length::Units(TEXT_SIZE / 2)).into()
+        }
+        Block::Paragraph(inlines) => line(context, inlines, Style::default(), TEXT_SIZE),
+        Block::Heading(level, inlines) => line(
+            context,
+            inlines,
+            Style {
+                bold: true,
+                ..Style::default()
+            },
+            HEADING_SIZES[(*level).clamp(1, 3) as usize - 1],
+        ),
+        Block::Quote(blocks) => container(
+            container(blocks_column(context, blocks))
+                .style(Container::Background)
+                .padding([0, 0, 0, 8]),
+        )
+        .style(Container::QuoteBar)
+        .padding([0, 0, 0, 4])
+        .into(),
+        Block::ListItem {
+            number,
+            depth,
+            content,
+        } => {
+            let bullet = match number {
+                Some(number) => format!("{number}."),
+                None => String::from("•"),
+            };
+
+            row![
+                text(bullet).size(TEXT_SIZE),
+                line(context, content, Style::default(), TEXT_SIZE)
+            ]
+            .spacing(5)
+            .padding([0, 0, 0, *depth as u16 * LIST_INDENT])
+            .into()
+        }
+        Block::CodeBlock { language, code } => code_block(language.as_deref(), code),
+    }
+}
+
+/// Highlighted code that scrolls horizontally instead of wrapping.
+/// The wheel only scrolls it while shift is held, so the chat keeps scrolling over it
+fn code_block<'a, Backend>(
+    language: Option<&str>,
+    code: &str,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
+{
+    let lines = highlight(language, code)
+        .into_iter()
+        .map(|line| {
+            // Empty lines would have no height
+            if line.is_empty() {
+                return text(" ").font(MONOSPACE).size(CODE_SIZE).into();
+            }
+
+            Row::with_children(
+                line.into_iter()
+                    .map(|(kind, content)| {
+                        text(content)
+                            .font(MONOSPACE)
+                            .size(CODE_SIZE)
+                            .style(Text::Highlight(kind))
+                            .into()
+                    })
+                    .collect(),
+            )
+            .into()
+        })
+        .collect();
+
+    let header = row![
+        text(language.unwrap_or_default().to_string())
+            .size(12)
+            .style(Text::Weak),
+        horizontal_space(Length::Fill),
+        button(text("Copy").size(12))
+            .style(Button::TransparentHover(false, Some(5.0)))
+            .padding([2, 6])
+            .on_press(TextChatEvent::CopyCode(code.to_string()))
+    ]
+    .align_items(Alignment::Center);
+
+    container(
+        column![
+            header,
+            horizontal_wheel(
+                scrollable(column(lines).padding([0, 0, 8, 0]))
+                    .horizontal_scroll(scrollable::Properties::new())
+                    .style(Scrollable::Weak)
+            )
+        ]
+        .spacing(2),
+    )
+    .style(Container::Code(5.0))
+    .width(Length::Fill)
+    .padding(8)
+    .into()
+}
+
+fn blocks_column<'a, Backend>(
+    context: &Context,
+    blocks: &[Block],
+) -> Column<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    column(blocks.iter().map(|b| block(context, b)).collect()).spacing(2)
+}
+
+/// Renders the markdown of a message
+pub fn markdown<'a, Backend>(
+    content: &str,
+    message_id: Snowflake,
+    channel_id: Snowflake,
+    state: &State,
+    revealed_spoilers: &HashSet<(Snowflake, usize)>,
+    cache: &MarkdownCache,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    let context = Context {
+        message_id,
+        channel_id,
+        state,
+        revealed_spoilers,
+    };
+
+    let mut cache = cache.0.borrow_mut();
+    let (parsed, blocks) = cache.entry(message_id).or_default();
+    if parsed != content {
+        *parsed = content.to_string();
+        *blocks = markdown::parse(content);
+    }
+
+    blocks_column(&context, blocks).into()
+}
@@ -1,36 +1,333 @@
-use std::collections::HashMap;
-
-use iced::{widget::text, Element};
+use iced::{
+    widget::{
+        button, column, container, horizontal_rule, horizontal_space, image, text, Column, Row,
+    },
+    Alignment, Element, Length,
+};
 use iced_graphics::Renderer;
+use iced_native::row;
 
 use crate::{
-    data::{state::Message as ChatMessage, user::User},
-    gui::theme::Theme,
+    data::{
+        message::{Message as ChatMessage, MessageKind, MessageStatus, Reaction},
+        snowflake::Snowflake,
+        state::State,
+        timestamp::{self, TimestampStyle},
+    },
+    gui::{
+        components::{images::user_avatar, text_lines::text_lines, wrap::wrap},
+        theme::{Button, Container, Text, Theme},
+    },
 };
 
-pub fn message<'a, Message, Backend>(
+use super::{markdown::markdown, TextChatEvent, TextChatState};
+
+const AVATAR_SIZE: u16 = 40;
+
+/// How a message is placed in the chat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLayout {
+    /// First message of a group, below the avatar, name and time of its author
+    Header,
+    /// Following message of the same author
+    Continuation,
+    /// Single line with the time and author in front
+    Compact,
+}
+
+/// Nickname of the user in the guild of the channel, or its username
+fn display_name(state: &State, channel_id: Snowflake, user_id: Snowflake) -> &str {
+    state
+        .display_name(channel_id, user_id)
+        .unwrap_or("User not found")
+}
+
+/// Describes messages that are generated by discord
+fn system_message(message: &ChatMessage, username: &str) -> String {
+    match message.kind {
+        MessageKind::RecipientAdd => format!("{username} added someone to the group"),
+        MessageKind::RecipientRemove => format!("{username} removed someone from the group"),
+        MessageKind::Call => format!("{username} started a call"),
+        MessageKind::ChannelNameChange => {
+            format!("{username} changed the channel name to {}", message.content)
+        }
+        MessageKind::ChannelIconChange => format!("{username} changed the channel icon"),
+        MessageKind::ChannelPinnedMessage => format!("{username} pinned a message"),
+        MessageKind::UserJoin => format!("{username} joined"),
+        MessageKind::GuildBoost => format!("{username} boosted the server"),
+        MessageKind::ChannelFollowAdd => format!("{username} followed {}", message.content),
+        MessageKind::ThreadCreated => format!("{username} started a thread: {}", message.content),
+        _ => message.content.clone(),
+    }
+}
+
+fn reaction_chip<'a, Backend>(
+    message: &ChatMessage,
+    reaction: &'a Reaction,
+    state: &State,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + 'static,
+{
+    let handle = reaction
+        .emoji
+        .id
+        .and_then(|id| state.emojis.get(&id).cloned().flatten());
+
+    // Custom emojis are shown by name until they are loaded
+    let emoji: Element<_, _> = match (handle, &reaction.emoji.name) {
+        (Some(handle), _) => image(handle)
+            .width(Length::Units(16))
+            .height(Length::Units(16))
+            .into(),
+        (None, Some(name)) if reaction.emoji.id.is_none() => text(name).size(14).into(),
+        (None, Some(name)) => text(format!(":{name}:")).size(14).into(),
+        (None, None) => text(":emoji:").size(14).into(),
+    };
+
+    button(
+        row![emoji, text(reaction.count.to_string()).size(14)]
+            .spacing(5)
+            .align_items(Alignment::Center),
+    )
+    .style(Button::Border(reaction.me, Some(8.0), 1.0))
+    .padding([2, 8])
+    .on_press(TextChatEvent::ReactionPressed(
+        message.id,
+        reaction.emoji.clone(),
+        reaction.me,
+    ))
+    .into()
+}
+
+/// Struck through word by word, as the line is drawn below single lines of text
+fn deleted_content<'a, Backend>(
+    content: &str,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
+{
+    let lines = content.lines().map(|line| {
+        let words = line
+            .split_inclusive(' ')
+            .map(|word| {
+                text_lines(text(word.to_string()))
+                    .strikethrough(true)
+                    .into()
+            })
+            .collect();
+        wrap(words).into()
+    });
+
+    Column::with_children(lines.collect()).into()
+}
+
+pub fn message<'a, Backend>(
     message: &'a ChatMessage,
-    user_cache: &'a HashMap<String, User>,
-) -> Element<'a, Message, Renderer<Backend, Theme>>
+    channel_id: Snowflake,
+    state: &'a State,
+    chat: &TextChatState,
+    layout: MessageLayout,
+    can_edit: bool,
+    can_delete: bool,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
 where
     Backend: iced_graphics::Backend
         + iced_graphics::backend::Text
         + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
         + 'static,
 {
-    match message {
-        ChatMessage::Default { user_id, content } => {
-            let user = user_cache.get(user_id);
-            text(format!(
-                "{}: {}",
-                if let Some(user) = user {
-                    &user.username
-                } else {
-                    "User not found"
-                },
-                content
-            ))
-            .into()
+    let author = display_name(state, channel_id, message.author_id);
+
+    // System messages are not grouped and have no author header
+    if message.kind.is_system() {
+        let content = text(system_message(message, author)).style(Text::Weak);
+
+        return match layout {
+            MessageLayout::Compact => container(content),
+            _ => container(row![horizontal_space(Length::Units(AVATAR_SIZE)), content].spacing(10)),
         }
+        .width(Length::Fill)
+        .padding([2, 5])
+        .into();
     }
+
+    // Messages that are not sent or deleted are shown as they were written
+    let content: Element<_, _> = if message.status == MessageStatus::Sent && !message.deleted {
+        markdown(
+            &message.content,
+            message.id,
+            channel_id,
+            state,
+            &chat.revealed_spoilers,
+            &chat.markdown_cache,
+        )
+    } else if message.deleted {
+        deleted_content(&message.content)
+    } else {
+        text(&message.content)
+            .style(match message.status {
+                MessageStatus::Sent => Text::Default,
+                MessageStatus::Pending => Text::Weak,
+                MessageStatus::Failed => Text::Error,
+            })
+            .into()
+    };
+
+    let content: Element<_, _> = if layout == MessageLayout::Compact {
+        row![
+            text(timestamp::format(
+                message.timestamp,
+                TimestampStyle::ShortTime
+            ))
+            .size(12)
+            .style(Text::Weak),
+            text(format!("{author}:")),
+            content
+        ]
+        .spacing(5)
+        .into()
+    } else {
+        content
+    };
+
+    let mut line = row![content].spacing(10).align_items(Alignment::Center);
+
+    if message.edited_timestamp.is_some() {
+        line = line.push(text("(edited)").size(12).style(Text::Weak));
+    }
+
+    if message.deleted {
+        line = line.push(text("(deleted)").size(12).style(Text::Error));
+    }
+
+    if let (MessageStatus::Failed, Some(nonce)) = (&message.status, &message.nonce) {
+        line = line.push(
+            button(text("Retry").size(14))
+                .style(Button::Secondary(None))
+                .padding([2, 8])
+                .on_press(TextChatEvent::Retry(nonce.clone())),
+        );
+    }
+
+    if can_edit {
+        line = line.push(
+            button(text("Edit").size(12))
+                .style(Button::TransparentHover(false, Some(5.0)))
+                .padding([2, 6])
+                .on_press(TextChatEvent::EditPressed(message.id)),
+        );
+    }
+
+    if can_delete {
+        line = line.push(
+            button(text("Delete").size(12))
+                .style(Button::TransparentHover(false, Some(5.0)))
+                .padding([2, 6])
+                .on_press(TextChatEvent::DeletePressed(message.id)),
+        );
+    }
+
+    let mut message_column = column![].spacing(2);
+
+    if message.kind == MessageKind::Reply {
+        let reply = if let Some(referenced) = &message.referenced_message {
+            format!(
+                "Reply to {}: {}",
+                display_name(state, channel_id, referenced.author_id),
+                referenced.content
+            )
+        } else {
+            String::from("Reply to a deleted message")
+        };
+
+        message_column = message_column.push(text(reply).size(14).style(Text::Weak));
+    }
+
+    message_column = message_column.push(line);
+
+    for attachment in message.attachments.iter() {
+        message_column =
+            message_column.push(text(format!("[{}]", attachment.filename)).style(Text::Weak));
+    }
+
+    if !message.reactions.is_empty() {
+        message_column = message_column.push(
+            Row::with_children(
+                message
+                    .reactions
+                    .iter()
+                    .map(|r| reaction_chip(message, r, state))
+                    .collect(),
+            )
+            .spacing(5),
+        );
+    }
+
+    let content: Element<_, _> = match layout {
+        MessageLayout::Header => {
+            let avatar = match state.user_cache.get(&message.author_id) {
+                Some(user) => user_avatar(user, AVATAR_SIZE, false),
+                None => horizontal_space(Length::Units(AVATAR_SIZE)).into(),
+            };
+            let header = row![
+                text(author),
+                text(timestamp::calendar(message.timestamp))
+                    .size(12)
+                    .style(Text::Weak)
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center);
+
+            row![avatar, column![header, message_column].spacing(2)]
+                .spacing(10)
+                .into()
+        }
+        MessageLayout::Continuation => {
+            row![horizontal_space(Length::Units(AVATAR_SIZE)), message_column]
+                .spacing(10)
+                .into()
+        }
+        MessageLayout::Compact => message_column.into(),
+    };
+
+    // Groups are separated by the space above their header
+    let top_padding = if layout == MessageLayout::Header {
+        10
+    } else {
+        2
+    };
+
+    container(content)
+        .style(if state.mentions_me(channel_id, message) {
+            Container::Mentioned
+        } else {
+            Container::Transparent
+        })
+        .width(Length::Fill)
+        .padding([top_padding, 5, 2, 5])
+        .into()
+}
+
+/// Line with a label between messages, e.g. for the date of the following messages
+pub fn separator<'a, Backend>(
+    label: impl ToString,
+    style: Text,
+) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
+{
+    row![
+        horizontal_rule(1),
+        text(label.to_string()).size(12).style(style),
+        horizontal_rule(1)
+    ]
+    .spacing(10)
+    .padding([10, 5, 0, 5])
+    .align_items(Alignment::Center)
+    .into()
 }
@@ -1,18 +1,69 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
 use iced::{
-    widget::{column, scrollable, text},
-    Element,
+    keyboard::KeyCode,
+    widget::{button, column, container, horizontal_space, text, text_input, Column},
+    Element, Length,
 };
 use iced_graphics::Renderer;
 use iced_lazy::Component;
+use iced_native::{row, widget::Id};
 
-use crate::{data::state::State, gui::theme::Theme};
+use crate::{
+    data::{
+        message::{Message as ChatMessage, MessageKind, MessageStatus, ReactionEmoji},
+        permissions::Permissions,
+        snowflake::Snowflake,
+        state::{MessageHistory, State},
+        timestamp::{self, TimestampStyle},
+    },
+    gui::{
+        components::{
+            key_listener::key_listener,
+            virtual_list::{virtual_list, Viewport, Window},
+        },
+        theme::{Button, Container, Text, Theme},
+    },
+};
 
-use self::message::message;
+use self::{
+    markdown::MarkdownCache,
+    message::{message, separator, MessageLayout},
+};
 
+mod markdown;
 mod message;
 
+/// Minimum time between typing notifications, discord shows them for 10 seconds
+const TYPING_INTERVAL: Duration = Duration::from_secs(8);
+/// Maximum time between messages of the same author that are grouped together
+const GROUP_INTERVAL: time::Duration = time::Duration::minutes(7);
+
+/// Ids of the text inputs, to focus them and to find out which one is focused
+fn composer_id() -> text_input::Id {
+    text_input::Id::new("composer")
+}
+
+fn edit_input_id() -> text_input::Id {
+    text_input::Id::new("edit-message")
+}
+
+/// "X is typing...", with up to three names
+fn typing_text(names: &[&str]) -> Option<String> {
+    Some(match names {
+        [] => return None,
+        [a] => format!("{a} is typing..."),
+        [a, b] => format!("{a} and {b} are typing..."),
+        [a, b, c] => format!("{a}, {b} and {c} are typing..."),
+        _ => String::from("Several people are typing..."),
+    })
+}
+
 pub fn text_chat<'a, Message>(
-    channel_id: String,
+    channel_id: Snowflake,
     state: &'a State,
     on_message: impl Fn(TextChatMessage) -> Message + 'static,
 ) -> TextChat<'a, Message> {
@@ -21,35 +72,238 @@ pub fn text_chat<'a, Message>(
 
 #[derive(Debug, Clone)]
 pub enum TextChatMessage {
-    Default,
+    /// Request the page of messages before the given message id or the latest messages if there is none
+    LoadHistory {
+        channel_id: Snowflake,
+        before: Option<Snowflake>,
+    },
+    SendMessage {
+        channel_id: Snowflake,
+        content: String,
+    },
+    RetryMessage {
+        channel_id: Snowflake,
+        nonce: String,
+    },
+    /// The current user is typing in the channel
+    Typing { channel_id: Snowflake },
+    AddReaction {
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        emoji: ReactionEmoji,
+    },
+    RemoveReaction {
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        emoji: ReactionEmoji,
+    },
+    EditMessage {
+        channel_id: Snowflake,
+        message_id: Snowflake,
+        content: String,
+    },
+    DeleteMessage {
+        channel_id: Snowflake,
+        message_id: Snowflake,
+    },
+    /// Copies the content to the clipboard
+    CopyToClipboard(String),
+    /// Focuses a text input, e.g. the input of a message that is edited
+    FocusInput(text_input::Id),
+}
+
+/// Message whose content is replaced by the composer
+struct Editing {
+    channel_id: Snowflake,
+    message_id: Snowflake,
+    content: String,
 }
 
 #[derive(Default)]
-pub struct TextChatState;
+pub struct TextChatState {
+    /// Unsent composer content per channel
+    drafts: HashMap<Snowflake, String>,
+    /// When the last typing notification was sent in each channel
+    last_typing: HashMap<Snowflake, Instant>,
+    editing: Option<Editing>,
+    /// Message that waits for the delete confirmation
+    deleting: Option<(Snowflake, Snowflake)>,
+    /// Message id and index of the spoilers that were clicked
+    revealed_spoilers: HashSet<(Snowflake, usize)>,
+    markdown_cache: MarkdownCache,
+    /// Scroll position in each channel
+    viewports: HashMap<Snowflake, Viewport<Snowflake>>,
+}
 
 #[derive(Debug, Clone)]
 pub enum TextChatEvent {
-    Default,
+    Scrolled(Viewport<Snowflake>),
+    DraftChanged(String),
+    Send,
+    Retry(String),
+    /// message id, emoji, whether the current user already reacted with it
+    ReactionPressed(Snowflake, ReactionEmoji, bool),
+    /// Edits the last own message if the composer is empty
+    EditLast,
+    EditPressed(Snowflake),
+    EditChanged(String),
+    SubmitEdit,
+    DeletePressed(Snowflake),
+    ConfirmDelete,
+    /// Cancels editing or deleting a message
+    Cancel,
+    /// message id, spoiler index
+    SpoilerPressed(Snowflake, usize),
+    /// Copies the code of a code block
+    CopyCode(String),
 }
 
 pub struct TextChat<'a, Message> {
-    channel_id: String,
+    channel_id: Snowflake,
     state: &'a State,
+    permissions: Permissions,
+    compact: bool,
     on_message: Box<dyn Fn(TextChatMessage) -> Message>,
 }
 
 impl<'a, Message> TextChat<'a, Message> {
     fn new(
-        channel_id: String,
+        channel_id: Snowflake,
         state: &'a State,
         on_message: impl Fn(TextChatMessage) -> Message + 'static,
     ) -> Self {
         Self {
             channel_id,
             state,
+            permissions: Permissions::PRIVATE_CHANNEL,
+            compact: false,
             on_message: Box::new(on_message),
         }
     }
+
+    /// Permissions of the current user in the channel. Defaults to the permissions of private channels
+    pub fn permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Shows every message in one line instead of grouping them under their author
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    fn message(&self, id: Snowflake) -> Option<&'a ChatMessage> {
+        self.state.message_cache.get(&self.channel_id)?.get(&id)
+    }
+
+    /// Only sent messages of the current user that were written by it can be edited
+    fn can_edit(&self, message: &ChatMessage) -> bool {
+        message.author_id == self.state.user_id
+            && message.status == MessageStatus::Sent
+            && !message.kind.is_system()
+            && !message.deleted
+    }
+
+    fn can_delete(&self, message: &ChatMessage) -> bool {
+        (message.author_id == self.state.user_id
+            || self.permissions.contains(Permissions::MANAGE_MESSAGES))
+            && message.status == MessageStatus::Sent
+            && !message.deleted
+    }
+
+    /// Returns the message that focuses the input of the edited message
+    fn start_editing(&self, state: &mut TextChatState, message: &ChatMessage) -> Message {
+        state.deleting = None;
+        state.editing = Some(Editing {
+            channel_id: self.channel_id,
+            message_id: message.id,
+            content: message.content.clone(),
+        });
+
+        (self.on_message)(TextChatMessage::FocusInput(edit_input_id()))
+    }
+
+    /// Messages are grouped under the header of the previous message if it was sent
+    /// by the same author shortly before
+    fn grouped(previous: &ChatMessage, message: &ChatMessage) -> bool {
+        previous.author_id == message.author_id
+            && !previous.kind.is_system()
+            && message.kind != MessageKind::Reply
+            && message.timestamp - previous.timestamp < GROUP_INTERVAL
+    }
+
+    /// Index of the first message of another user after the message that was read last
+    /// before the channel was opened
+    fn first_unread(&self, messages: &[&ChatMessage]) -> Option<usize> {
+        let last_read = *self.state.unread_dividers.get(&self.channel_id)?;
+        let start = messages.partition_point(|m| m.id <= last_read);
+
+        // Own messages are read once they are sent
+        (start..messages.len()).find(|i| messages[*i].author_id != self.state.user_id)
+    }
+
+    /// Message with the date separator and the unread divider above it
+    fn message_row<Backend>(
+        &self,
+        state: &TextChatState,
+        messages: &[&'a ChatMessage],
+        index: usize,
+        first_unread: Option<usize>,
+    ) -> Element<'a, TextChatEvent, Renderer<Backend, Theme>>
+    where
+        Backend: iced_graphics::Backend
+            + iced_graphics::backend::Text
+            + iced_graphics::backend::Image
+            + iced_graphics::backend::Svg
+            + 'static,
+    {
+        let m = messages[index];
+        let previous = index.checked_sub(1).map(|i| messages[i]);
+        let mut row = Column::new();
+
+        let new_day = previous.map_or(true, |p| {
+            timestamp::local(p.timestamp).date() != timestamp::local(m.timestamp).date()
+        });
+        if new_day {
+            row = row.push(separator(
+                timestamp::format(m.timestamp, TimestampStyle::LongDate),
+                Text::Weak,
+            ));
+        }
+
+        let unread = first_unread == Some(index);
+        if unread {
+            row = row.push(separator("New messages", Text::Error));
+        }
+
+        let layout = if self.compact {
+            MessageLayout::Compact
+        } else if !new_day && !unread && previous.map_or(false, |p| Self::grouped(p, m)) {
+            MessageLayout::Continuation
+        } else {
+            MessageLayout::Header
+        };
+
+        row.push(message(
+            m,
+            self.channel_id,
+            self.state,
+            state,
+            layout,
+            self.can_edit(m),
+            self.can_delete(m),
+        ))
+        .into()
+    }
+
+    /// The edited message if it is in this channel
+    fn editing<'s>(&self, state: &'s TextChatState) -> Option<&'s Editing> {
+        state
+            .editing
+            .as_ref()
+            .filter(|e| e.channel_id == self.channel_id)
+    }
 }
 
 impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>> for TextChat<'a, Message>
@@ -63,26 +317,290 @@ where
     type State = TextChatState;
     type Event = TextChatEvent;
 
-    fn update(&mut self, _state: &mut Self::State, _event: Self::Event) -> Option<Message> {
-        None
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            TextChatEvent::Scrolled(viewport) => {
+                state.viewports.insert(self.channel_id, viewport);
+                let messages = self.state.message_cache.get(&self.channel_id)?;
+                let before = messages.keys().next().copied();
+
+                // Load older messages once the oldest loaded message is built
+                let oldest_built = match viewport.window {
+                    Window::Bottom(count) => count >= messages.len(),
+                    Window::Range(first, _) => before.map_or(false, |id| first <= id),
+                };
+
+                if oldest_built
+                    && self.state.message_history(self.channel_id) == MessageHistory::Partial
+                {
+                    Some((self.on_message)(TextChatMessage::LoadHistory {
+                        channel_id: self.channel_id,
+                        before,
+                    }))
+                } else {
+                    None
+                }
+            }
+            TextChatEvent::DraftChanged(draft) => {
+                let typing = !draft.is_empty()
+                    && state
+                        .last_typing
+                        .get(&self.channel_id)
+                        .map_or(true, |sent| sent.elapsed() >= TYPING_INTERVAL);
+                state.drafts.insert(self.channel_id, draft);
+
+                if typing {
+                    state.last_typing.insert(self.channel_id, Instant::now());
+                    Some((self.on_message)(TextChatMessage::Typing {
+                        channel_id: self.channel_id,
+                    }))
+                } else {
+                    None
+                }
+            }
+            TextChatEvent::Send if !self.permissions.contains(Permissions::SEND_MESSAGES) => None,
+            TextChatEvent::Send => {
+                let content = state.drafts.remove(&self.channel_id).unwrap_or_default();
+                // Sending a message ends the typing indicator
+                state.last_typing.remove(&self.channel_id);
+                if content.trim().is_empty() {
+                    None
+                } else {
+                    Some((self.on_message)(TextChatMessage::SendMessage {
+                        channel_id: self.channel_id,
+                        content,
+                    }))
+                }
+            }
+            TextChatEvent::Retry(nonce) => Some((self.on_message)(TextChatMessage::RetryMessage {
+                channel_id: self.channel_id,
+                nonce,
+            })),
+            TextChatEvent::ReactionPressed(message_id, emoji, true) => {
+                Some((self.on_message)(TextChatMessage::RemoveReaction {
+                    channel_id: self.channel_id,
+                    message_id,
+                    emoji,
+                }))
+            }
+            // Joining a reaction that is already on the message does not need ADD_REACTIONS
+            TextChatEvent::ReactionPressed(_, _, false)
+                if !self.permissions.contains(Permissions::READ_MESSAGE_HISTORY) =>
+            {
+                None
+            }
+            TextChatEvent::ReactionPressed(message_id, emoji, false) => {
+                Some((self.on_message)(TextChatMessage::AddReaction {
+                    channel_id: self.channel_id,
+                    message_id,
+                    emoji,
+                }))
+            }
+            TextChatEvent::EditLast => {
+                let composer_empty = state
+                    .drafts
+                    .get(&self.channel_id)
+                    .map_or(true, String::is_empty);
+
+                if !composer_empty || self.editing(state).is_some() {
+                    return None;
+                }
+
+                let message = self
+                    .state
+                    .message_cache
+                    .get(&self.channel_id)
+                    .and_then(|msgs| msgs.values().rev().find(|m| self.can_edit(m)))?;
+                Some(self.start_editing(state, message))
+            }
+            TextChatEvent::EditPressed(id) => {
+                let message = self.message(id).filter(|m| self.can_edit(m))?;
+                Some(self.start_editing(state, message))
+            }
+            TextChatEvent::EditChanged(content) => {
+                if let Some(editing) = &mut state.editing {
+                    editing.content = content;
+                }
+                None
+            }
+            TextChatEvent::SubmitEdit => {
+                let editing = state.editing.take()?;
+                let message = self.message(editing.message_id)?;
+
+                // Like in the discord client, removing all content deletes the message
+                if editing.content.trim().is_empty() {
+                    state.deleting = Some((self.channel_id, editing.message_id));
+                    None
+                } else if editing.content == message.content {
+                    None
+                } else {
+                    Some((self.on_message)(TextChatMessage::EditMessage {
+                        channel_id: self.channel_id,
+                        message_id: editing.message_id,
+                        content: editing.content,
+                    }))
+                }
+            }
+            TextChatEvent::DeletePressed(id) => {
+                if self.message(id).map_or(false, |m| self.can_delete(m)) {
+                    state.editing = None;
+                    state.deleting = Some((self.channel_id, id));
+                }
+                None
+            }
+            TextChatEvent::ConfirmDelete => {
+                let (channel_id, message_id) = state.deleting.take()?;
+                Some((self.on_message)(TextChatMessage::DeleteMessage {
+                    channel_id,
+                    message_id,
+                }))
+            }
+            TextChatEvent::Cancel => {
+                state.editing = None;
+                state.deleting = None;
+                None
+            }
+            TextChatEvent::SpoilerPressed(message_id, index) => {
+                state.revealed_spoilers.insert((message_id, index));
+                None
+            }
+            TextChatEvent::CopyCode(code) => {
+                Some((self.on_message)(TextChatMessage::CopyToClipboard(code)))
+            }
+        }
     }
 
     fn view(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
     ) -> iced_native::Element<'_, Self::Event, Renderer<Backend, Theme>> {
-        let messages = self.state.message_cache.get(&self.channel_id);
-        if let Some(messages) = messages {
-            scrollable(column(
-                messages
-                    .into_iter()
-                    .map(|m| message(m, &self.state.user_cache))
-                    .collect(),
-            ))
+        // Only the markdown of the messages of the open channel is kept
+        state
+            .markdown_cache
+            .evict(self.state.message_cache.get(&self.channel_id));
+
+        let messages: Element<_, _> =
+            if let Some(messages) = self.state.message_cache.get(&self.channel_id) {
+                let ids = messages.keys().copied().collect::<Vec<_>>();
+                let messages = messages.values().collect::<Vec<_>>();
+                let first_unread = self.first_unread(&messages);
+                let viewport = state
+                    .viewports
+                    .get(&self.channel_id)
+                    .copied()
+                    .unwrap_or_default();
+
+                virtual_list(
+                    ids,
+                    viewport,
+                    |i| self.message_row(state, &messages, i, first_unread),
+                    TextChatEvent::Scrolled,
+                )
+                .into()
+            } else if self.state.message_history(self.channel_id) == MessageHistory::Loading {
+                container(text("Loading messages..."))
+                    .height(Length::Fill)
+                    .into()
+            } else {
+                container(text("No messages")).height(Length::Fill).into()
+            };
+
+        let deleting = state
+            .deleting
+            .filter(|(channel_id, _)| *channel_id == self.channel_id)
+            .and_then(|(_, id)| self.message(id));
+
+        let composer: Element<_, _> = if let Some(deleted) = deleting {
+            container(
+                column![
+                    text("Delete this message?"),
+                    text(&deleted.content).size(14).style(Text::Weak),
+                    row![
+                        horizontal_space(Length::Fill),
+                        button("Cancel")
+                            .style(Button::Secondary(None))
+                            .padding([5, 12])
+                            .on_press(TextChatEvent::Cancel),
+                        button("Delete")
+                            .style(Button::Primary(None))
+                            .padding([5, 12])
+                            .on_press(TextChatEvent::ConfirmDelete)
+                    ]
+                    .spacing(10)
+                ]
+                .spacing(5),
+            )
+            .style(Container::BackgroundWeak(10.0))
+            .width(Length::Fill)
+            .padding(10)
+            .into()
+        } else if let Some(editing) = self.editing(state) {
+            column![
+                text("Editing message, escape to cancel")
+                    .size(14)
+                    .style(Text::Weak),
+                text_input("Edit message", &editing.content, TextChatEvent::EditChanged)
+                    .id(edit_input_id())
+                    .on_submit(TextChatEvent::SubmitEdit)
+                    .padding(10)
+            ]
+            .spacing(5)
+            .into()
+        } else if self.permissions.contains(Permissions::SEND_MESSAGES) {
+            text_input(
+                "Message",
+                state
+                    .drafts
+                    .get(&self.channel_id)
+                    .map(String::as_str)
+                    .unwrap_or(""),
+                TextChatEvent::DraftChanged,
+            )
+            .id(composer_id())
+            .on_submit(TextChatEvent::Send)
+            .padding(10)
             .into()
         } else {
-            text("No messages").into()
-        }
+            container(
+                text("You do not have permission to send messages in this channel")
+                    .style(Text::Weak),
+            )
+            .padding(10)
+            .into()
+        };
+
+        let typing_names = self
+            .state
+            .typing_users(self.channel_id)
+            .into_iter()
+            .map(|user| {
+                self.state
+                    .display_name(self.channel_id, user.id)
+                    .unwrap_or(&user.username)
+            })
+            .collect::<Vec<_>>();
+        let typing = text(typing_text(&typing_names).unwrap_or_default())
+            .size(14)
+            .style(Text::Weak);
+
+        let content = column![messages, column![composer, typing].spacing(5)]
+            .width(Length::Fill)
+            .spacing(10)
+            .padding(15);
+
+        // Like in the discord client, arrow up only edits while typing in the composer
+        let composer_id = Id::from(composer_id());
+        key_listener(
+            content,
+            move |key_code, modifiers, focused| match key_code {
+                KeyCode::Up if modifiers.is_empty() && focused == Some(&composer_id) => {
+                    Some(TextChatEvent::EditLast)
+                }
+                KeyCode::Escape => Some(TextChatEvent::Cancel),
+                _ => None,
+            },
+        )
+        .into()
     }
 }
 
@@ -0,0 +1,165 @@
+use iced_native::{
+    event, layout, mouse, renderer,
+    widget::{Tree, Widget},
+    Background, Clipboard, Color, Element, Event, Layout, Length, Point, Rectangle, Shell,
+};
+
+/// Lines are drawn at these fractions of the height of a line of text
+const UNDERLINE_POSITION: f32 = 0.9;
+const STRIKETHROUGH_POSITION: f32 = 0.55;
+const THICKNESS: f32 = 1.0;
+
+/// Underlines or strikes through a single line of text, in the color of the text
+pub fn text_lines<'a, Message, Renderer>(
+    content: impl Into<Element<'a, Message, Renderer>>,
+) -> TextLines<'a, Message, Renderer> {
+    TextLines {
+        content: content.into(),
+        underline: false,
+        strikethrough: false,
+        color: None,
+    }
+}
+
+pub struct TextLines<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,
+    underline: bool,
+    strikethrough: bool,
+    color: Option<Color>,
+}
+
+impl<'a, Message, Renderer> TextLines<'a, Message, Renderer> {
+    pub fn underline(mut self, underline: bool) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    pub fn strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
+        self
+    }
+
+    /// Color of the lines if the text does not have the default color
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for TextLines<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        );
+
+        let bounds = layout.bounds();
+        let color = self.color.unwrap_or(style.text_color);
+        let lines = [
+            (self.underline, UNDERLINE_POSITION),
+            (self.strikethrough, STRIKETHROUGH_POSITION),
+        ];
+
+        for (_, position) in lines.into_iter().filter(|(drawn, _)| *drawn) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        y: (bounds.y + bounds.height * position).round(),
+                        height: THICKNESS,
+                        ..bounds
+                    },
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                Background::Color(color),
+            );
+        }
+    }
+}
+
+impl<'a, Message, Renderer> From<TextLines<'a, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(text_lines: TextLines<'a, Message, Renderer>) -> Self {
+        Element::new(text_lines)
+    }
+}
@@ -0,0 +1,610 @@
+use std::{collections::HashMap, hash::Hash, ops::Range};
+
+use iced_native::{
+    event, layout, mouse, renderer,
+    widget::{
+        scrollable::StyleSheet,
+        tree::{self, Tree},
+        Widget,
+    },
+    Clipboard, Element, Event, Layout, Length, Point, Rectangle, Shell, Size, Vector,
+};
+
+/// Rows that are built above and below the visible rows
+const OVERSCAN: usize = 10;
+/// Rows that are built before any heights are known
+const INITIAL_ROWS: usize = 30;
+/// Height of rows that were never laid out while no row was measured
+const ESTIMATED_HEIGHT: f32 = 50.0;
+/// Pixels scrolled per line of the mouse wheel
+const LINE_HEIGHT: f32 = 60.0;
+const SCROLLBAR_WIDTH: f32 = 10.0;
+const MIN_SCROLLER_HEIGHT: f32 = 20.0;
+
+/// Position that is kept when rows are added or removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor<K> {
+    /// Sticks to the bottom, so new rows are scrolled into view
+    Bottom,
+    /// The top of the list is `offset` pixels below the top of the row
+    Row { key: K, offset: f32 },
+}
+
+/// Rows that are built, the other rows are only known by their key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window<K> {
+    /// The given number of rows at the bottom
+    Bottom(usize),
+    /// Rows from the first to the last key
+    Range(K, K),
+}
+
+impl<K: Ord> Window<K> {
+    fn indices(&self, keys: &[K]) -> Range<usize> {
+        match self {
+            Window::Bottom(count) => keys.len().saturating_sub(*count)..keys.len(),
+            // The keys might have been removed since the window was published
+            Window::Range(first, last) => {
+                let start = keys.partition_point(|k| k < first);
+                let end = keys.partition_point(|k| k <= last);
+                start..end.max(start)
+            }
+        }
+    }
+}
+
+/// Scroll position of a virtual list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport<K> {
+    pub anchor: Anchor<K>,
+    pub window: Window<K>,
+}
+
+impl<K> Default for Viewport<K> {
+    fn default() -> Self {
+        Self {
+            anchor: Anchor::Bottom,
+            window: Window::Bottom(INITIAL_ROWS),
+        }
+    }
+}
+
+/// Scrollable list that only builds and lays out the rows in its window. The window follows
+/// the visible rows and is published with the scroll position, so the rows can be built
+/// for the next view. `keys` have to be sorted
+pub fn virtual_list<'a, K, Message, Renderer>(
+    keys: Vec<K>,
+    viewport: Viewport<K>,
+    row: impl FnMut(usize) -> Element<'a, Message, Renderer>,
+    on_scroll: impl Fn(Viewport<K>) -> Message + 'a,
+) -> VirtualList<'a, K, Message, Renderer>
+where
+    K: Copy + Ord,
+{
+    let window = viewport.window.indices(&keys);
+
+    VirtualList {
+        start: window.start,
+        rows: window.map(row).collect(),
+        keys,
+        viewport,
+        on_scroll: Box::new(on_scroll),
+    }
+}
+
+pub struct VirtualList<'a, K, Message, Renderer> {
+    keys: Vec<K>,
+    viewport: Viewport<K>,
+    /// Index of the first built row
+    start: usize,
+    rows: Vec<Element<'a, Message, Renderer>>,
+    on_scroll: Box<dyn Fn(Viewport<K>) -> Message + 'a>,
+}
+
+struct State<K> {
+    /// Heights of the rows when they were last laid out
+    heights: HashMap<K, f32>,
+    /// Width at which the heights were measured
+    width: f32,
+    /// Keys of the built rows, so rows keep their widget state when the window moves
+    rows: Vec<K>,
+    /// Viewport that was published since the last view, so events add up until the next one
+    published: Option<Viewport<K>>,
+    /// Distance of the cursor from the top of the scroller while it is dragged
+    grabbed_at: Option<f32>,
+}
+
+impl<K: Copy + Eq + Hash> State<K> {
+    /// Remembers the heights of the built rows. Rows laid out at another width have other heights
+    fn measure(&mut self, keys: &[K], layout: Layout<'_>) {
+        let width = layout.bounds().width;
+        if width != self.width {
+            self.heights.clear();
+            self.width = width;
+        }
+
+        for (key, row) in keys.iter().zip(layout.children()) {
+            self.heights.insert(*key, row.bounds().height);
+        }
+    }
+}
+
+/// All rows at the current scroll position
+struct Position {
+    /// Top of every row and the bottom of the last one
+    tops: Vec<f32>,
+    offset: f32,
+    /// Vertical translation of the built rows from their layout to the screen
+    translation: f32,
+}
+
+impl Position {
+    fn height(&self) -> f32 {
+        self.tops[self.tops.len() - 1]
+    }
+
+    /// Index of the row at `y`, or of the last row below the list
+    fn row_at(&self, y: f32) -> usize {
+        self.tops[..self.tops.len() - 1]
+            .partition_point(|top| *top <= y)
+            .saturating_sub(1)
+    }
+
+    /// Cursor position in the layout of the built rows, if it is over the list
+    fn cursor(&self, bounds: Rectangle, cursor_position: Point) -> Point {
+        if bounds.contains(cursor_position) {
+            Point::new(cursor_position.x, cursor_position.y - self.translation)
+        } else {
+            Point::new(-1.0, -1.0)
+        }
+    }
+}
+
+struct Scrollbar {
+    track: Rectangle,
+    scroller: Rectangle,
+    max_offset: f32,
+}
+
+impl Scrollbar {
+    /// Only lists that are higher than their bounds have a scrollbar
+    fn new(bounds: Rectangle, position: &Position) -> Option<Self> {
+        let height = position.height();
+        if height <= bounds.height {
+            return None;
+        }
+
+        let track = Rectangle {
+            x: bounds.x + bounds.width - SCROLLBAR_WIDTH,
+            width: SCROLLBAR_WIDTH,
+            ..bounds
+        };
+        let max_offset = height - bounds.height;
+        let scroller_height = (bounds.height / height * bounds.height)
+            .max(MIN_SCROLLER_HEIGHT)
+            .min(bounds.height);
+
+        Some(Self {
+            scroller: Rectangle {
+                y: track.y + position.offset / max_offset * (track.height - scroller_height),
+                height: scroller_height,
+                ..track
+            },
+            track,
+            max_offset,
+        })
+    }
+
+    /// Scroll offset at which the top of the scroller is at `y`
+    fn offset_at(&self, y: f32) -> f32 {
+        let range = (self.track.height - self.scroller.height).max(1.0);
+        ((y - self.track.y) / range * self.max_offset).clamp(0.0, self.max_offset)
+    }
+}
+
+impl<'a, K, Message, Renderer> VirtualList<'a, K, Message, Renderer>
+where
+    K: Copy + Ord,
+{
+    fn row_keys(&self) -> &[K] {
+        &self.keys[self.start..self.start + self.rows.len()]
+    }
+
+    fn current(&self, state: &State<K>) -> Viewport<K> {
+        state.published.unwrap_or(self.viewport)
+    }
+
+    /// Rows that are not built have their last measured height or the average height
+    fn position(&self, state: &State<K>, layout: Layout<'_>, anchor: Anchor<K>) -> Position
+    where
+        K: Hash,
+    {
+        let estimate = if state.heights.is_empty() {
+            ESTIMATED_HEIGHT
+        } else {
+            state.heights.values().sum::<f32>() / state.heights.len() as f32
+        };
+
+        let built = self.start..self.start + self.rows.len();
+        let mut rows = layout.children();
+        let mut tops = Vec::with_capacity(self.keys.len() + 1);
+        let mut top = 0.0;
+
+        for (i, key) in self.keys.iter().enumerate() {
+            tops.push(top);
+            let height = if built.contains(&i) {
+                rows.next().map(|row| row.bounds().height)
+            } else {
+                state.heights.get(key).copied()
+            };
+            top += height.unwrap_or(estimate);
+        }
+        tops.push(top);
+
+        let max_offset = (top - layout.bounds().height).max(0.0);
+        let offset = match anchor {
+            Anchor::Bottom => max_offset,
+            Anchor::Row { key, offset } => {
+                let row = self.keys.partition_point(|k| *k < key);
+                (tops[row] + offset).clamp(0.0, max_offset)
+            }
+        };
+
+        Position {
+            translation: tops[self.start] - offset,
+            tops,
+            offset,
+        }
+    }
+
+    fn anchor(&self, position: &Position, height: f32, offset: f32) -> Anchor<K> {
+        let max_offset = (position.height() - height).max(0.0);
+        let offset = offset.clamp(0.0, max_offset);
+        if offset >= max_offset - 1.0 {
+            return Anchor::Bottom;
+        }
+
+        let row = position.row_at(offset);
+        Anchor::Row {
+            key: self.keys[row],
+            offset: offset - position.tops[row],
+        }
+    }
+
+    /// The current window is kept while it covers the visible rows and half of the overscan,
+    /// otherwise the visible rows and the full overscan are requested
+    fn window(
+        &self,
+        position: &Position,
+        height: f32,
+        anchor: Anchor<K>,
+        current: Window<K>,
+    ) -> Window<K> {
+        let len = self.keys.len();
+        if len == 0 {
+            return current;
+        }
+
+        let first = position.row_at(position.offset);
+        let end = position.row_at(position.offset + height) + 1;
+        let target = first.saturating_sub(OVERSCAN)..(end + OVERSCAN).min(len);
+
+        let built = current.indices(&self.keys);
+        let covered = built.start <= first.saturating_sub(OVERSCAN / 2)
+            && built.end >= (end + OVERSCAN / 2).min(len);
+        let oversized = built.len() > target.len() + 2 * OVERSCAN;
+        let bottom = matches!(anchor, Anchor::Bottom);
+
+        if covered && !oversized && bottom == matches!(current, Window::Bottom(_)) {
+            current
+        } else if bottom {
+            Window::Bottom(target.len())
+        } else {
+            Window::Range(self.keys[target.start], self.keys[target.end - 1])
+        }
+    }
+}
+
+impl<'a, K, Message, Renderer> Widget<Message, Renderer> for VirtualList<'a, K, Message, Renderer>
+where
+    K: Copy + Ord + Hash + 'static,
+    Renderer: iced_native::Renderer,
+    Renderer::Theme: StyleSheet,
+    <Renderer::Theme as StyleSheet>::Style: Default,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State<K>>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State {
+            heights: HashMap::new(),
+            width: 0.0,
+            rows: self.row_keys().to_vec(),
+            published: None,
+            grabbed_at: None,
+        })
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.rows.iter().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<State<K>>();
+        state.published = None;
+
+        let mut previous = state
+            .rows
+            .drain(..)
+            .zip(tree.children.drain(..))
+            .collect::<HashMap<_, _>>();
+        state.rows = self.row_keys().to_vec();
+
+        tree.children = self
+            .rows
+            .iter()
+            .zip(&state.rows)
+            .map(|(row, key)| match previous.remove(key) {
+                Some(mut child) => {
+                    child.diff(row);
+                    child
+                }
+                None => Tree::new(row),
+            })
+            .collect();
+    }
+
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
+        Length::Fill
+    }
+
+    /// Only the built rows are laid out, below each other from the top of the list
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits.width(Length::Fill).height(Length::Fill).max();
+        let row_limits = layout::Limits::new(
+            Size::ZERO,
+            Size::new((size.width - SCROLLBAR_WIDTH).max(0.0), f32::INFINITY),
+        );
+
+        let mut top = 0.0;
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut node = row.as_widget().layout(renderer, &row_limits);
+                node.move_to(Point::new(0.0, top));
+                top += node.size().height;
+                node
+            })
+            .collect();
+
+        layout::Node::with_children(size, rows)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State<K>>();
+        state.measure(self.row_keys(), layout);
+
+        let current = self.current(state);
+        let position = self.position(state, layout, current.anchor);
+        let scrollbar = Scrollbar::new(bounds, &position);
+        let over_scrollbar = scrollbar
+            .as_ref()
+            .map_or(false, |s| s.track.contains(cursor_position));
+
+        let cursor = if over_scrollbar || state.grabbed_at.is_some() {
+            Point::new(-1.0, -1.0)
+        } else {
+            position.cursor(bounds, cursor_position)
+        };
+        let mut status = self
+            .rows
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .map(|((row, tree), layout)| {
+                row.as_widget_mut().on_event(
+                    tree,
+                    event.clone(),
+                    layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge);
+
+        let mut offset = position.offset;
+        if status == event::Status::Ignored {
+            match (event, &scrollbar) {
+                (Event::Mouse(mouse::Event::WheelScrolled { delta }), _)
+                    if bounds.contains(cursor_position) =>
+                {
+                    offset -= match delta {
+                        mouse::ScrollDelta::Lines { y, .. } => y * LINE_HEIGHT,
+                        mouse::ScrollDelta::Pixels { y, .. } => y,
+                    };
+                    status = event::Status::Captured;
+                }
+                (
+                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                    Some(scrollbar),
+                ) if over_scrollbar => {
+                    // Clicking next to the scroller centers it on the cursor
+                    let grabbed_at = if scrollbar.scroller.contains(cursor_position) {
+                        cursor_position.y - scrollbar.scroller.y
+                    } else {
+                        scrollbar.scroller.height / 2.0
+                    };
+                    state.grabbed_at = Some(grabbed_at);
+                    offset = scrollbar.offset_at(cursor_position.y - grabbed_at);
+                    status = event::Status::Captured;
+                }
+                (Event::Mouse(mouse::Event::CursorMoved { .. }), Some(scrollbar)) => {
+                    if let Some(grabbed_at) = state.grabbed_at {
+                        offset = scrollbar.offset_at(cursor_position.y - grabbed_at);
+                        status = event::Status::Captured;
+                    }
+                }
+                (Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)), _) => {
+                    if state.grabbed_at.take().is_some() {
+                        status = event::Status::Captured;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let anchor = if offset == position.offset {
+            current.anchor
+        } else {
+            self.anchor(&position, bounds.height, offset)
+        };
+        let position = if anchor == current.anchor {
+            position
+        } else {
+            self.position(state, layout, anchor)
+        };
+        let viewport = Viewport {
+            anchor,
+            window: self.window(&position, bounds.height, anchor, current.window),
+        };
+
+        if viewport != current {
+            state.published = Some(viewport);
+            shell.publish((self.on_scroll)(viewport));
+        }
+
+        status
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State<K>>();
+        let position = self.position(state, layout, self.current(state).anchor);
+
+        let over_scrollbar =
+            Scrollbar::new(bounds, &position).map_or(false, |s| s.track.contains(cursor_position));
+        if over_scrollbar || state.grabbed_at.is_some() {
+            return mouse::Interaction::Idle;
+        }
+
+        let cursor = position.cursor(bounds, cursor_position);
+        let viewport = Rectangle {
+            y: bounds.y - position.translation,
+            ..bounds
+        };
+
+        self.rows
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .map(|((row, tree), layout)| {
+                row.as_widget()
+                    .mouse_interaction(tree, layout, cursor, &viewport, renderer)
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_ref::<State<K>>();
+        let position = self.position(state, layout, self.current(state).anchor);
+
+        let cursor = position.cursor(bounds, cursor_position);
+        let viewport = Rectangle {
+            y: bounds.y - position.translation,
+            ..bounds
+        };
+
+        renderer.with_layer(bounds, |renderer| {
+            renderer.with_translation(Vector::new(0.0, position.translation), |renderer| {
+                for ((row, tree), layout) in
+                    self.rows.iter().zip(&tree.children).zip(layout.children())
+                {
+                    row.as_widget()
+                        .draw(tree, renderer, theme, style, layout, cursor, &viewport);
+                }
+            });
+        });
+
+        if let Some(scrollbar) = Scrollbar::new(bounds, &position) {
+            let scrollbar_style = Default::default();
+            let appearance =
+                if state.grabbed_at.is_some() || scrollbar.track.contains(cursor_position) {
+                    theme.hovered(&scrollbar_style)
+                } else {
+                    theme.active(&scrollbar_style)
+                };
+
+            if let Some(background) = appearance.background {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scrollbar.track,
+                        border_radius: appearance.border_radius,
+                        border_width: appearance.border_width,
+                        border_color: appearance.border_color,
+                    },
+                    background,
+                );
+            }
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: scrollbar.scroller,
+                    border_radius: appearance.scroller.border_radius,
+                    border_width: appearance.scroller.border_width,
+                    border_color: appearance.scroller.border_color,
+                },
+                appearance.scroller.color,
+            );
+        }
+    }
+}
+
+impl<'a, K, Message, Renderer> From<VirtualList<'a, K, Message, Renderer>>
+    for Element<'a, Message, Renderer>
+where
+    K: Copy + Ord + Hash + 'static,
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+    Renderer::Theme: StyleSheet,
+    <Renderer::Theme as StyleSheet>::Style: Default,
+{
+    fn from(virtual_list: VirtualList<'a, K, Message, Renderer>) -> Self {
+        Element::new(virtual_list)
+    }
+}
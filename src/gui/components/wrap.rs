@@ -0,0 +1,179 @@
+use iced_native::{
+    event, layout, mouse, overlay, renderer,
+    widget::{Tree, Widget},
+    Clipboard, Element, Event, Layout, Length, Point, Rectangle, Shell, Size,
+};
+
+/// Places its children from left to right and continues on the next line
+/// when a child does not fit. Children are centered vertically in their line
+pub fn wrap<'a, Message, Renderer>(
+    children: Vec<Element<'a, Message, Renderer>>,
+) -> Wrap<'a, Message, Renderer> {
+    Wrap { children }
+}
+
+pub struct Wrap<'a, Message, Renderer> {
+    children: Vec<Element<'a, Message, Renderer>>,
+}
+
+/// Centers the children of the line starting at `start` and returns the height of the line
+fn finish_line(nodes: &mut [layout::Node], start: usize, top: f32) -> f32 {
+    let line = &mut nodes[start..];
+    let height = line.iter().map(|n| n.size().height).fold(0.0, f32::max);
+
+    for node in line {
+        let x = node.bounds().x;
+        node.move_to(Point::new(x, top + (height - node.size().height) / 2.0));
+    }
+
+    height
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for Wrap<'a, Message, Renderer>
+where
+    Renderer: iced_native::Renderer,
+{
+    fn width(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.children.iter().map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(&self.children);
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let max_width = limits.max().width;
+        let child_limits = layout::Limits::new(Size::ZERO, Size::new(max_width, f32::INFINITY));
+
+        let mut nodes: Vec<layout::Node> = Vec::with_capacity(self.children.len());
+        let mut line_start = 0;
+        let (mut left, mut top, mut width) = (0.0, 0.0, 0.0_f32);
+
+        for child in &self.children {
+            let mut node = child.as_widget().layout(renderer, &child_limits);
+            let child_width = node.size().width;
+
+            if left > 0.0 && left + child_width > max_width {
+                top += finish_line(&mut nodes, line_start, top);
+                line_start = nodes.len();
+                left = 0.0;
+            }
+
+            node.move_to(Point::new(left, 0.0));
+            left += child_width;
+            width = width.max(left);
+            nodes.push(node);
+        }
+        top += finish_line(&mut nodes, line_start, top);
+
+        layout::Node::with_children(Size::new(width.min(max_width), top), nodes)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.children
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .map(|((child, tree), layout)| {
+                child.as_widget_mut().on_event(
+                    tree,
+                    event.clone(),
+                    layout,
+                    cursor_position,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            })
+            .fold(event::Status::Ignored, event::Status::merge)
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .map(|((child, tree), layout)| {
+                child.as_widget().mouse_interaction(
+                    tree,
+                    layout,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        for ((child, tree), layout) in self
+            .children
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+        {
+            child.as_widget().draw(
+                tree,
+                renderer,
+                theme,
+                style,
+                layout,
+                cursor_position,
+                viewport,
+            );
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        overlay::from_children(&mut self.children, tree, layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<Wrap<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: iced_native::Renderer + 'a,
+{
+    fn from(wrap: Wrap<'a, Message, Renderer>) -> Self {
+        Element::new(wrap)
+    }
+}
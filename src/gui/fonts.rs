@@ -0,0 +1,40 @@
+use iced::Font;
+
+/// Default font of the application. The bold and italic fonts belong to the same family
+pub const REGULAR: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/res/fonts/DejaVuSans.ttf"
+));
+
+pub const BOLD: Font = Font::External {
+    name: "DejaVu Sans Bold",
+    bytes: include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/res/fonts/DejaVuSans-Bold.ttf"
+    )),
+};
+
+pub const ITALIC: Font = Font::External {
+    name: "DejaVu Sans Oblique",
+    bytes: include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/res/fonts/DejaVuSans-Oblique.ttf"
+    )),
+};
+
+pub const BOLD_ITALIC: Font = Font::External {
+    name: "DejaVu Sans Bold Oblique",
+    bytes: include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/res/fonts/DejaVuSans-BoldOblique.ttf"
+    )),
+};
+
+/// Font of code in messages
+pub const MONOSPACE: Font = Font::External {
+    name: "DejaVu Sans Mono",
+    bytes: include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/res/fonts/DejaVuSansMono.ttf"
+    )),
+};
@@ -10,3 +10,17 @@ pub static SETTINGS: Lazy<svg::Handle> =
 pub static USERS: Lazy<svg::Handle> =
     Lazy::new(|| svg::Handle::from_path(format!("{}/users.svg", *PATH)));
 pub static X: Lazy<svg::Handle> = Lazy::new(|| svg::Handle::from_path(format!("{}/x.svg", *PATH)));
+pub static HASH: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/hash.svg", *PATH)));
+pub static ANNOUNCEMENT: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/announcement.svg", *PATH)));
+pub static VOICE: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/voice.svg", *PATH)));
+pub static STAGE: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/stage.svg", *PATH)));
+pub static FORUM: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/forum.svg", *PATH)));
+pub static CHEVRON_DOWN: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/chevron_down.svg", *PATH)));
+pub static CHEVRON_RIGHT: Lazy<svg::Handle> =
+    Lazy::new(|| svg::Handle::from_path(format!("{}/chevron_right.svg", *PATH)));
@@ -3,13 +3,25 @@ use std::sync::Arc;
 use iced::widget::image;
 
 use crate::{
-    api::gateway::{Gateway, GatewayEvent},
-    data::{settings::Settings, state::State, user::User},
+    api::{
+        gateway::{Gateway, GatewayEvent},
+        rest_client::data::{ChannelData, MessageData},
+    },
+    data::{
+        message::Message,
+        settings::Settings,
+        snowflake::Snowflake,
+        state::{RelationshipKind, State},
+        user::User,
+    },
 };
 
 use super::{
     components::guildbar::View,
-    views::{private_channels::PrivateChannelsViewMessage, settings::SettingsViewMessage},
+    views::{
+        guild::GuildViewMessage, private_channels::PrivateChannelsViewMessage,
+        settings::SettingsViewMessage, status::StatusViewMessage,
+    },
 };
 
 pub type Result<T> = core::result::Result<T, Arc<anyhow::Error>>;
@@ -28,16 +40,40 @@ pub enum AppMessage {
     SettingsLoaded(Settings),
     SettingsSaved(Result<()>),
     AccountLoaded(Result<User>, Option<String>),
-    AccountAvatarLoaded(String, Result<image::Handle>),
+    AccountAvatarLoaded(Snowflake, Result<image::Handle>),
     GatewayConnected(Result<(Gateway, State)>),
 
     GatewayEvent(GatewayEvent),
+    /// channel id, requested before id, messages
+    MessagesLoaded(Snowflake, Option<Snowflake>, Result<Vec<MessageData>>),
+    /// channel id, nonce, created message
+    MessageSent(Snowflake, String, Result<MessageData>),
+    /// channel id, message id, content before the edit, edited message
+    MessageEdited(Snowflake, Snowflake, String, Result<MessageData>),
+    /// channel id, deleted message to restore if deleting failed
+    MessageDeleted(Snowflake, Box<Message>, Result<()>),
+    /// recipient id, created channel
+    PrivateChannelCreated(Snowflake, Result<ChannelData>),
+    /// user id, new relationship or `None` if it was removed
+    RelationshipChanged(Snowflake, Option<RelationshipKind>, Result<()>),
+    /// username
+    FriendRequestSent(String, Result<()>),
+    ReactionUpdated(Result<()>),
+    TypingTriggered(Result<()>),
+    /// Removes typing indicators that expired
+    TypingTick,
+    StatusUpdated(Result<()>),
+    CustomStatusExpired,
 
-    UserAvatarLoaded(String, Result<image::Handle>),
-    GroupIconLoaded(String, Result<image::Handle>),
+    UserAvatarLoaded(Snowflake, Result<image::Handle>),
+    GroupIconLoaded(Snowflake, Result<image::Handle>),
+    GuildIconLoaded(Snowflake, Result<image::Handle>),
+    EmojiLoaded(Snowflake, Result<image::Handle>),
 
     ViewSelect(View),
 
     SettingsViewMessage(SettingsViewMessage),
     DirectMessagesViewMessage(PrivateChannelsViewMessage),
+    GuildViewMessage(GuildViewMessage),
+    StatusViewMessage(StatusViewMessage),
 }
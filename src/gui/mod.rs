@@ -1,3 +1,4 @@
+pub mod fonts;
 pub mod theme;
 
 mod components;
@@ -5,36 +6,134 @@ mod icons;
 mod message;
 mod views;
 
-use iced::{executor, widget::text, Application, Command, Element, Renderer, Subscription};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use iced::{
+    executor,
+    widget::{text, text_input},
+    Application, Command, Element, Renderer, Subscription,
+};
 use iced_native::row;
-use tracing::error;
+use tracing::{error, info, warn};
 
 use crate::{
     api::{
         cdn_client::CdnClient,
         gateway::{Gateway, GatewayEvent},
+        rest_client::RestClient,
     },
     data::{
+        guild::Guild,
+        markdown,
+        message::{Message, MessageStatus},
         settings::Settings,
-        state::{ConnectionState, Message},
-        user::User,
+        snowflake::Snowflake,
+        state::{ConnectionState, MessageHistory, RelationshipKind, State},
+        user::{CustomStatus, Presence, User},
     },
 };
 
 use self::{
-    components::guildbar::{guildbar, View},
+    components::{
+        guildbar::{guildbar, View},
+        text_chat::TextChatMessage,
+    },
     message::{map_result_message, AppMessage},
     theme::{
         data::{DefaultThemes, ThemeData},
         Theme,
     },
     views::{
-        private_channels::private_channels_view,
+        guild::{guild_view, GuildViewMessage},
+        private_channels::{
+            private_channels_view, FriendRequest, FriendsMessage, PrivateChannelsViewMessage,
+        },
         settings::{settings_view, AccountsMessage, SettingsViewMessage},
+        status::{status_view, StatusViewMessage},
     },
 };
 
 const SERVICE: &str = "strife_accounts";
+const MESSAGE_PAGE_SIZE: u8 = 50;
+
+fn load_user_avatar(cdn_client: &CdnClient, user: &User) -> Command<AppMessage> {
+    let id = user.id;
+    let map_result = map_result_message(move |handle| AppMessage::UserAvatarLoaded(id, handle));
+
+    if let Some(avatar) = &user.avatar {
+        Command::perform(
+            cdn_client.clone().avatar(id, avatar.clone(), 64),
+            map_result,
+        )
+    } else {
+        Command::perform(
+            cdn_client.clone().default_avatar(user.discriminator),
+            map_result,
+        )
+    }
+}
+
+/// Loads the icon of a guild unless it has none or it was already loaded
+fn load_guild_icon(cdn_client: &CdnClient, guild: &Guild) -> Command<AppMessage> {
+    if let (Some(icon), None) = (&guild.icon, &guild.icon_handle) {
+        let id = guild.id;
+        Command::perform(
+            cdn_client.clone().guild_icon(id, icon.clone(), 96),
+            map_result_message(move |handle| AppMessage::GuildIconLoaded(id, handle)),
+        )
+    } else {
+        Command::none()
+    }
+}
+
+/// Loads the images of custom emojis that were not requested before
+/// Caches users and loads the avatars that are not loaded yet, once per user
+fn cache_users<'u>(
+    cdn_client: &CdnClient,
+    state: &mut State,
+    users: impl IntoIterator<Item = &'u User>,
+) -> Command<AppMessage> {
+    let mut loading = HashSet::new();
+    let commands = users
+        .into_iter()
+        .filter(|user| state.cache_user((*user).clone()) && loading.insert(user.id))
+        .map(|user| load_user_avatar(cdn_client, user))
+        .collect::<Vec<_>>();
+
+    Command::batch(commands)
+}
+
+fn load_emojis(
+    cdn_client: &CdnClient,
+    state: &mut State,
+    ids: impl IntoIterator<Item = Snowflake>,
+) -> Command<AppMessage> {
+    Command::batch(state.emojis_to_load(ids).into_iter().map(|id| {
+        Command::perform(
+            cdn_client.clone().emoji(id, 48),
+            map_result_message(move |handle| AppMessage::EmojiLoaded(id, handle)),
+        )
+    }))
+}
+
+/// Clears the custom status once it expired. Discord clears the saved status on its own,
+/// but the activity of the open sessions has to be removed by the client
+fn expire_custom_status(custom_status: &CustomStatus) -> Command<AppMessage> {
+    if let Some(expires_at) = custom_status.expires_at {
+        let duration = (expires_at - time::OffsetDateTime::now_utc())
+            .try_into()
+            .unwrap_or_default();
+
+        Command::perform(tokio::time::sleep(duration), |_| {
+            AppMessage::CustomStatusExpired
+        })
+    } else {
+        Command::none()
+    }
+}
 
 pub struct App {
     connection_state: ConnectionState,
@@ -42,9 +141,272 @@ pub struct App {
     active_view: View,
     accounts: Vec<User>,
     cdn_client: CdnClient,
+    rest_client: RestClient,
+    /// Friend request form of the friends tab, which is updated by the result of sending it
+    friend_request: FriendRequest,
+    /// Why the direct message channel with a user could not be opened
+    direct_message_errors: HashMap<Snowflake, String>,
 }
 
 impl App {
+    fn connect(&mut self, token: String) -> Command<AppMessage> {
+        self.connection_state = ConnectionState::Connecting;
+        self.rest_client = self.rest_client.with_token(token.clone());
+
+        Command::perform(
+            Gateway::new(token),
+            map_result_message(AppMessage::GatewayConnected),
+        )
+    }
+
+    fn text_chat_message(&mut self, message: TextChatMessage) -> Command<AppMessage> {
+        if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+            match message {
+                TextChatMessage::LoadHistory { channel_id, before } => {
+                    let history = state.message_history(channel_id);
+
+                    // The latest messages are requested when a channel is opened. Channels
+                    // that are not loaded yet are read once their messages are loaded
+                    if before.is_none()
+                        && matches!(history, MessageHistory::Partial | MessageHistory::Complete)
+                    {
+                        state.read_channel(channel_id);
+                    }
+
+                    let should_load = if before.is_some() {
+                        history == MessageHistory::Partial
+                    } else {
+                        history == MessageHistory::NotLoaded
+                    };
+
+                    if should_load {
+                        state
+                            .message_history
+                            .insert(channel_id, MessageHistory::Loading);
+
+                        return Command::perform(
+                            self.rest_client.clone().messages(
+                                channel_id,
+                                MESSAGE_PAGE_SIZE,
+                                before,
+                            ),
+                            map_result_message(move |msgs| {
+                                AppMessage::MessagesLoaded(channel_id, before, msgs)
+                            }),
+                        );
+                    }
+                }
+                TextChatMessage::SendMessage {
+                    channel_id,
+                    content,
+                } => {
+                    // Show the message right away and reconcile it once discord created it
+                    let nonce = Snowflake::now();
+                    state.insert_message(
+                        channel_id,
+                        Message::pending(nonce, state.user_id, content.clone()),
+                    );
+
+                    let nonce = nonce.to_string();
+                    return Command::perform(
+                        self.rest_client
+                            .clone()
+                            .create_message(channel_id, content, nonce.clone()),
+                        map_result_message(move |msg| {
+                            AppMessage::MessageSent(channel_id, nonce, msg)
+                        }),
+                    );
+                }
+                TextChatMessage::RetryMessage { channel_id, nonce } => {
+                    let content = state.message_cache.get(&channel_id).and_then(|msgs| {
+                        msgs.values()
+                            .find(|msg| {
+                                msg.status == MessageStatus::Failed
+                                    && msg.nonce.as_ref() == Some(&nonce)
+                            })
+                            .map(|msg| msg.content.clone())
+                    });
+
+                    if let Some(content) = content {
+                        state.set_message_status(channel_id, &nonce, MessageStatus::Pending);
+
+                        return Command::perform(
+                            self.rest_client.clone().create_message(
+                                channel_id,
+                                content,
+                                nonce.clone(),
+                            ),
+                            map_result_message(move |msg| {
+                                AppMessage::MessageSent(channel_id, nonce, msg)
+                            }),
+                        );
+                    }
+                }
+                TextChatMessage::AddReaction {
+                    channel_id,
+                    message_id,
+                    emoji,
+                } => {
+                    // The reaction is counted once the gateway sends it
+                    return Command::perform(
+                        self.rest_client
+                            .clone()
+                            .add_reaction(channel_id, message_id, emoji),
+                        map_result_message(AppMessage::ReactionUpdated),
+                    );
+                }
+                TextChatMessage::RemoveReaction {
+                    channel_id,
+                    message_id,
+                    emoji,
+                } => {
+                    return Command::perform(
+                        self.rest_client
+                            .clone()
+                            .remove_own_reaction(channel_id, message_id, emoji),
+                        map_result_message(AppMessage::ReactionUpdated),
+                    );
+                }
+                TextChatMessage::EditMessage {
+                    channel_id,
+                    message_id,
+                    content,
+                } => {
+                    // Show the edit right away and restore the old content if it fails
+                    let mut previous = None;
+                    state.update_message(channel_id, message_id, |msg| {
+                        previous = Some(std::mem::replace(&mut msg.content, content.clone()));
+                    });
+
+                    if let Some(previous) = previous {
+                        return Command::perform(
+                            self.rest_client
+                                .clone()
+                                .edit_message(channel_id, message_id, content),
+                            map_result_message(move |msg| {
+                                AppMessage::MessageEdited(channel_id, message_id, previous, msg)
+                            }),
+                        );
+                    }
+                }
+                TextChatMessage::DeleteMessage {
+                    channel_id,
+                    message_id,
+                } => {
+                    if let Some(msg) = state.take_message(channel_id, message_id) {
+                        let msg = Box::new(msg);
+                        return Command::perform(
+                            self.rest_client
+                                .clone()
+                                .delete_message(channel_id, message_id),
+                            map_result_message(move |res| {
+                                AppMessage::MessageDeleted(channel_id, msg, res)
+                            }),
+                        );
+                    }
+                }
+                TextChatMessage::CopyToClipboard(content) => {
+                    return iced::clipboard::write(content);
+                }
+                TextChatMessage::FocusInput(id) => return text_input::focus(id),
+                TextChatMessage::Typing { channel_id } => {
+                    if self.settings.send_typing_indicator {
+                        return Command::perform(
+                            self.rest_client.clone().trigger_typing(channel_id),
+                            map_result_message(AppMessage::TypingTriggered),
+                        );
+                    }
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn friends_message(&mut self, message: FriendsMessage) -> Command<AppMessage> {
+        match message {
+            FriendsMessage::OpenDirectMessage(user_id) => {
+                self.direct_message_errors.remove(&user_id);
+
+                Command::perform(
+                    self.rest_client.clone().create_private_channel(user_id),
+                    map_result_message(move |channel| {
+                        AppMessage::PrivateChannelCreated(user_id, channel)
+                    }),
+                )
+            }
+            FriendsMessage::FriendRequestChanged(input) => {
+                self.friend_request.input = input;
+                self.friend_request.error = None;
+                Command::none()
+            }
+            FriendsMessage::SendFriendRequest {
+                username,
+                discriminator,
+            } => {
+                self.friend_request.sending = true;
+                self.friend_request.error = None;
+
+                Command::perform(
+                    self.rest_client
+                        .clone()
+                        .send_friend_request(username.clone(), discriminator),
+                    map_result_message(move |res| AppMessage::FriendRequestSent(username, res)),
+                )
+            }
+            FriendsMessage::AcceptRequest(user_id) => Command::perform(
+                self.rest_client.clone().accept_friend_request(user_id),
+                map_result_message(move |res| {
+                    AppMessage::RelationshipChanged(user_id, Some(RelationshipKind::Friend), res)
+                }),
+            ),
+            FriendsMessage::Block(user_id) => Command::perform(
+                self.rest_client.clone().block_user(user_id),
+                map_result_message(move |res| {
+                    AppMessage::RelationshipChanged(user_id, Some(RelationshipKind::Blocked), res)
+                }),
+            ),
+            FriendsMessage::RemoveRelationship(user_id) => Command::perform(
+                self.rest_client.clone().remove_relationship(user_id),
+                map_result_message(move |res| AppMessage::RelationshipChanged(user_id, None, res)),
+            ),
+        }
+    }
+
+    fn set_status(
+        &mut self,
+        presence: Presence,
+        custom_status: Option<CustomStatus>,
+    ) -> Command<AppMessage> {
+        if let ConnectionState::Connecetd(state, gateway) = &mut self.connection_state {
+            // The presence is confirmed by SESSIONS_REPLACE, but the picker should update right away
+            if let Some(user) = state.user_cache.get_mut(&state.user_id) {
+                user.presence = presence;
+            }
+            state.custom_status = custom_status.clone();
+
+            return Command::batch([
+                Command::perform(
+                    gateway
+                        .clone()
+                        .update_presence(presence, custom_status.clone()),
+                    map_result_message(AppMessage::StatusUpdated),
+                ),
+                Command::perform(
+                    self.rest_client
+                        .clone()
+                        .update_presence_settings(presence, custom_status.clone()),
+                    map_result_message(AppMessage::StatusUpdated),
+                ),
+                custom_status
+                    .as_ref()
+                    .map_or(Command::none(), expire_custom_status),
+            ]);
+        }
+
+        Command::none()
+    }
+
     fn save_settings(&self) -> Command<AppMessage> {
         Command::perform(
             self.settings.clone().save(),
@@ -67,6 +429,9 @@ impl Application for App {
                 active_view: View::DirectMessages,
                 accounts: vec![],
                 cdn_client: CdnClient::new(),
+                rest_client: RestClient::default(),
+                friend_request: FriendRequest::default(),
+                direct_message_errors: HashMap::new(),
             },
             Command::perform(Settings::load(), AppMessage::SettingsLoaded),
         )
@@ -87,7 +452,7 @@ impl Application for App {
                     .filter_map(|id| keyring::Entry::new(SERVICE, &id).get_password().ok())
                     .map(|token| {
                         Command::perform(
-                            User::from_token(token),
+                            self.rest_client.with_token(token).current_user(),
                             map_result_message(|user| AppMessage::AccountLoaded(user, None)),
                         )
                     })
@@ -98,12 +463,7 @@ impl Application for App {
                     if let Ok(token) =
                         keyring::Entry::new(SERVICE, &self.settings.active_account).get_password()
                     {
-                        self.connection_state = ConnectionState::Connecting;
-
-                        commands.push(Command::perform(
-                            Gateway::new(token),
-                            map_result_message(AppMessage::GatewayConnected),
-                        ));
+                        commands.push(self.connect(token));
                     } else {
                         self.connection_state = ConnectionState::Disconnected;
                         error!("Keyring did not contain the token of the selected account");
@@ -121,27 +481,31 @@ impl Application for App {
             }
             AppMessage::AccountLoaded(user, token) => match user {
                 Ok(user) => {
-                    let (id, discriminator, avatar) = (
-                        user.id.clone(),
-                        user.discriminator.clone(),
-                        user.avatar.clone(),
-                    );
+                    let (id, discriminator, avatar) =
+                        (user.id, user.discriminator, user.avatar.clone());
 
-                    if let None = self.settings.accounts.iter().find(|a| **a == id) {
+                    if let None = self
+                        .settings
+                        .accounts
+                        .iter()
+                        .find(|a| **a == id.to_string())
+                    {
                         if let Some(token) = token {
-                            if let Err(e) = keyring::Entry::new(SERVICE, &id).set_password(&token) {
+                            if let Err(e) =
+                                keyring::Entry::new(SERVICE, &id.to_string()).set_password(&token)
+                            {
                                 error!("Failed to save account token to keyring: {e}");
                             }
                         }
 
                         self.accounts.push(user);
-                        self.settings.accounts.push(id.clone());
+                        self.settings.accounts.push(id.to_string());
 
                         return Command::batch([
                             self.save_settings(),
                             if let Some(avatar) = avatar {
                                 Command::perform(
-                                    self.cdn_client.clone().avatar(id.clone(), avatar, 64),
+                                    self.cdn_client.clone().avatar(id, avatar, 64),
                                     map_result_message(|handle| {
                                         AppMessage::AccountAvatarLoaded(id, handle)
                                     }),
@@ -169,32 +533,23 @@ impl Application for App {
             }
             AppMessage::GatewayConnected(res) => match res {
                 Ok((gateway, state)) => {
-                    self.connection_state = ConnectionState::Connecetd(state.clone(), gateway);
+                    self.connection_state =
+                        ConnectionState::Connecetd(state.clone(), gateway.clone());
+                    self.friend_request = FriendRequest::default();
+                    self.direct_message_errors.clear();
 
                     // Create commands to load user avatars
-                    let user_commands = state.user_cache.into_iter().map(|(_, user)| {
-                        if let Some(avatar) = user.avatar {
-                            Command::perform(
-                                self.cdn_client.clone().avatar(user.id.clone(), avatar, 64),
-                                map_result_message(|handle| {
-                                    AppMessage::UserAvatarLoaded(user.id, handle)
-                                }),
-                            )
-                        } else {
-                            Command::perform(
-                                self.cdn_client.clone().default_avatar(user.discriminator),
-                                map_result_message(|handle| {
-                                    AppMessage::UserAvatarLoaded(user.id, handle)
-                                }),
-                            )
-                        }
-                    });
+                    let user_commands = state
+                        .user_cache
+                        .values()
+                        .map(|u| load_user_avatar(&self.cdn_client, u))
+                        .collect::<Vec<_>>();
 
                     // Create commands to load group icons
                     let group_commands = state.private_channels.into_iter().flat_map(|c| {
                         if let Some(icon) = c.icon {
                             Some(Command::perform(
-                                self.cdn_client.clone().channel_icon(c.id.clone(), icon, 64),
+                                self.cdn_client.clone().channel_icon(c.id, icon, 64),
                                 map_result_message(|handle| {
                                     AppMessage::GroupIconLoaded(c.id, handle)
                                 }),
@@ -204,7 +559,34 @@ impl Application for App {
                         }
                     });
 
-                    return Command::batch(user_commands.chain(group_commands));
+                    let guild_commands = state
+                        .guilds
+                        .iter()
+                        .map(|g| load_guild_icon(&self.cdn_client, g))
+                        .collect::<Vec<_>>();
+
+                    let expire_command = state
+                        .custom_status
+                        .as_ref()
+                        .map_or(Command::none(), expire_custom_status);
+
+                    // New sessions start online, the saved status has to be sent again
+                    let presence = state
+                        .user_cache
+                        .get(&state.user_id)
+                        .map_or(Presence::Online, |u| u.presence);
+                    let presence_command = Command::perform(
+                        gateway.update_presence(presence, state.custom_status.clone()),
+                        map_result_message(AppMessage::StatusUpdated),
+                    );
+
+                    return Command::batch(
+                        user_commands
+                            .into_iter()
+                            .chain(group_commands)
+                            .chain(guild_commands)
+                            .chain([expire_command, presence_command]),
+                    );
                 }
                 Err(e) => {
                     self.connection_state = ConnectionState::Disconnected;
@@ -217,28 +599,359 @@ impl Application for App {
                     if let Ok(token) =
                         keyring::Entry::new(SERVICE, &self.settings.active_account).get_password()
                     {
-                        self.connection_state = ConnectionState::Connecting;
-                        return Command::perform(
-                            Gateway::new(token),
-                            map_result_message(AppMessage::GatewayConnected),
-                        );
+                        return self.connect(token);
                     } else {
                         self.connection_state = ConnectionState::Disconnected;
                         error!("Keyring did not contain the token of the selected account");
                     }
                 }
-                GatewayEvent::Message(msg) => {
+                GatewayEvent::MessageCreated(msg) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.stop_typing(msg.channel_id, msg.author.id);
+                        let avatar_commands = cache_users(&self.cdn_client, state, msg.users());
+                        if let (Some(guild_id), Some(member)) = (msg.guild_id, msg.member.clone()) {
+                            state.cache_member(guild_id, msg.author.id, member);
+                        }
+
+                        let (channel_id, nonce) = (msg.channel_id, msg.nonce());
+                        let msg: Message = msg.into();
+                        let emojis = msg.custom_emojis();
+
+                        if let Some(nonce) = nonce {
+                            state.reconcile_message(channel_id, &nonce, msg);
+                        } else {
+                            state.insert_message(channel_id, msg);
+                        }
+
+                        return Command::batch([
+                            avatar_commands,
+                            load_emojis(&self.cdn_client, state, emojis),
+                        ]);
+                    }
+                }
+                GatewayEvent::MessageUpdated(update) => {
                     if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
-                        state.insert_message(
+                        let emojis = update
+                            .content
+                            .as_deref()
+                            .map(|content| markdown::custom_emojis(content).collect::<Vec<_>>())
+                            .unwrap_or_default();
+
+                        // Edits of messages that were never loaded are not interesting
+                        if state
+                            .update_message(update.channel_id, update.id, |msg| update.apply(msg))
+                        {
+                            return load_emojis(&self.cdn_client, state, emojis);
+                        }
+                    }
+                }
+                GatewayEvent::MessageAcked(ack) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.read_states.insert(ack.channel_id, ack.message_id);
+                    }
+                }
+                GatewayEvent::MessageDeleted(msg) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.delete_messages(
                             msg.channel_id,
-                            Message::Default {
-                                user_id: msg.author.id,
-                                content: msg.content,
-                            },
-                        )
+                            &[msg.id],
+                            self.settings.show_deleted_messages,
+                        );
+                    }
+                }
+                GatewayEvent::MessagesDeleted(msgs) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.delete_messages(
+                            msgs.channel_id,
+                            &msgs.ids,
+                            self.settings.show_deleted_messages,
+                        );
+                    }
+                }
+                GatewayEvent::ReactionAdded(reaction) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let me = reaction.user_id == state.user_id;
+                        let emoji_id = reaction.emoji.id;
+                        if state.update_message(reaction.channel_id, reaction.message_id, |msg| {
+                            msg.add_reaction(reaction.emoji, me)
+                        }) {
+                            return load_emojis(&self.cdn_client, state, emoji_id);
+                        }
+                    }
+                }
+                GatewayEvent::ReactionRemoved(reaction) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let me = reaction.user_id == state.user_id;
+                        state.update_message(reaction.channel_id, reaction.message_id, |msg| {
+                            msg.remove_reaction(&reaction.emoji, me)
+                        });
+                    }
+                }
+                GatewayEvent::ReactionsRemoved(reactions) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.update_message(reactions.channel_id, reactions.message_id, |msg| {
+                            msg.reactions.clear()
+                        });
+                    }
+                }
+                GatewayEvent::EmojiReactionsRemoved(reactions) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.update_message(reactions.channel_id, reactions.message_id, |msg| {
+                            msg.remove_emoji_reactions(&reactions.emoji)
+                        });
+                    }
+                }
+                GatewayEvent::RelationshipAdded(relationship) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let load_avatar = state.cache_user(relationship.user.clone());
+                        state.set_relationship(
+                            relationship.id,
+                            RelationshipKind::from(relationship.kind),
+                        );
+
+                        if load_avatar {
+                            return load_user_avatar(&self.cdn_client, &relationship.user);
+                        }
+                    }
+                }
+                GatewayEvent::RelationshipRemoved(relationship) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.remove_relationship(relationship.id);
+                    }
+                }
+                GatewayEvent::PresenceUpdated(presence) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        // Presences of users that are not cached are not shown anywhere
+                        if let Some(user) = state.user_cache.get_mut(&presence.user.id) {
+                            presence.apply(user);
+                        }
+                    }
+                }
+                GatewayEvent::SessionsReplaced(sessions) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        if let Some(user) = state.user_cache.get_mut(&state.user_id) {
+                            sessions.apply(user);
+                        }
+                    }
+                }
+                GatewayEvent::TypingStarted(typing) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.start_typing(typing.channel_id, typing.user_id);
+
+                        // Members of guilds are often not cached yet
+                        if let Some(member) = typing.member {
+                            if state.cache_user(member.user.clone()) {
+                                return load_user_avatar(&self.cdn_client, &member.user);
+                            }
+                        }
+                    }
+                }
+                GatewayEvent::GuildCreated(guild) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let guild = guild.into_guild(state.user_id);
+                        if state.guild(guild.id).map_or(false, |g| g.unavailable) {
+                            info!("Guild {} is available again", guild.name);
+                        }
+
+                        let id = guild.id;
+                        state.insert_guild(guild);
+
+                        if let Some(guild) = state.guild(id) {
+                            return load_guild_icon(&self.cdn_client, guild);
+                        }
+                    }
+                }
+                GatewayEvent::GuildUpdated(update) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let id = update.id;
+                        state.update_guild(id, |guild| update.apply(guild));
+
+                        if let Some(guild) = state.guild(id) {
+                            return load_guild_icon(&self.cdn_client, guild);
+                        }
+                    }
+                }
+                GatewayEvent::GuildDeleted(guild) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        if guild.unavailable {
+                            warn!("Guild {} became unavailable", guild.id);
+                            state.update_guild(guild.id, |g| g.unavailable = true);
+                        } else {
+                            state.remove_guild(guild.id);
+
+                            if self.active_view == View::Guild(guild.id) {
+                                self.active_view = View::DirectMessages;
+                            }
+                        }
                     }
                 }
             },
+            AppMessage::MessageSent(channel_id, nonce, msg) => {
+                if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                    match msg {
+                        Ok(msg) => state.reconcile_message(channel_id, &nonce, msg.into()),
+                        Err(e) => {
+                            state.set_message_status(channel_id, &nonce, MessageStatus::Failed);
+                            error!("Failed to send message: {e}");
+                        }
+                    }
+                }
+            }
+            AppMessage::MessageEdited(channel_id, message_id, previous, msg) => {
+                if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                    match msg {
+                        Ok(msg) => state.insert_message(channel_id, msg.into()),
+                        Err(e) => {
+                            state.update_message(channel_id, message_id, |msg| {
+                                msg.content = previous
+                            });
+                            error!("Failed to edit message: {e}");
+                        }
+                    }
+                }
+            }
+            AppMessage::MessageDeleted(channel_id, msg, res) => {
+                if let Err(e) = res {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.insert_message(channel_id, *msg);
+                    }
+                    error!("Failed to delete message: {e}");
+                }
+            }
+            AppMessage::PrivateChannelCreated(user_id, channel) => match channel {
+                Ok(channel) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        let id = channel.id;
+                        let avatar_commands =
+                            cache_users(&self.cdn_client, state, &channel.recipients);
+
+                        if !state.private_channels.iter().any(|c| c.id == id) {
+                            state.private_channels.insert(0, channel.into());
+                        }
+
+                        return Command::batch([
+                            avatar_commands,
+                            self.text_chat_message(TextChatMessage::LoadHistory {
+                                channel_id: id,
+                                before: None,
+                            }),
+                        ]);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to open direct message: {e}");
+                    // The view shows the error instead of waiting for the channel
+                    self.direct_message_errors.insert(user_id, e.to_string());
+                }
+            },
+            AppMessage::RelationshipChanged(user_id, kind, res) => match res {
+                Ok(()) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        match kind {
+                            Some(kind) => state.set_relationship(user_id, kind),
+                            None => state.remove_relationship(user_id),
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to update relationship: {e}"),
+            },
+            AppMessage::FriendRequestSent(username, res) => {
+                self.friend_request.sending = false;
+                match &res {
+                    Ok(()) => self.friend_request.input.clear(),
+                    Err(e) => self.friend_request.error = Some(e.to_string()),
+                }
+
+                match res {
+                    // The request is added to the relationships by the gateway
+                    Ok(()) => info!("Sent friend request to {username}"),
+                    Err(e) => error!("Failed to send friend request to {username}: {e}"),
+                }
+            }
+            AppMessage::ReactionUpdated(res) => {
+                if let Err(e) = res {
+                    error!("Failed to update reaction: {e}");
+                }
+            }
+            AppMessage::TypingTriggered(res) => {
+                if let Err(e) = res {
+                    warn!("Failed to send typing indicator: {e}");
+                }
+            }
+            AppMessage::TypingTick => {
+                if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                    state.remove_expired_typing();
+                }
+            }
+            AppMessage::StatusUpdated(res) => {
+                if let Err(e) = res {
+                    error!("Failed to update status: {e}");
+                }
+            }
+            AppMessage::CustomStatusExpired => {
+                if let ConnectionState::Connecetd(state, _) = &self.connection_state {
+                    // The status might have been replaced since the timer was started
+                    if state
+                        .custom_status
+                        .as_ref()
+                        .map_or(false, |s| s.is_expired())
+                    {
+                        let presence = state
+                            .user_cache
+                            .get(&state.user_id)
+                            .map_or(Presence::Online, |u| u.presence);
+
+                        return self.set_status(presence, None);
+                    }
+                }
+            }
+            AppMessage::MessagesLoaded(channel_id, before, msgs) => {
+                if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                    match msgs {
+                        Ok(msgs) => {
+                            let history = if msgs.len() < MESSAGE_PAGE_SIZE as usize {
+                                MessageHistory::Complete
+                            } else {
+                                MessageHistory::Partial
+                            };
+                            state.message_history.insert(channel_id, history);
+
+                            // Users of older messages might not be known yet
+                            let avatar_commands = cache_users(
+                                &self.cdn_client,
+                                state,
+                                msgs.iter().flat_map(|msg| msg.users()),
+                            );
+
+                            let msgs = msgs.into_iter().map(Into::into).collect::<Vec<Message>>();
+                            let emojis = msgs
+                                .iter()
+                                .flat_map(Message::custom_emojis)
+                                .collect::<Vec<_>>();
+                            state.insert_messages(channel_id, msgs);
+                            if before.is_none() {
+                                state.read_channel(channel_id);
+                            }
+
+                            return Command::batch([
+                                avatar_commands,
+                                load_emojis(&self.cdn_client, state, emojis),
+                            ]);
+                        }
+                        Err(e) => {
+                            state.message_history.insert(
+                                channel_id,
+                                if before.is_some() {
+                                    MessageHistory::Partial
+                                } else {
+                                    MessageHistory::NotLoaded
+                                },
+                            );
+                            error!("Failed to load messages: {e}");
+                        }
+                    }
+                }
+            }
 
             AppMessage::UserAvatarLoaded(id, handle) => match handle {
                 Ok(handle) => {
@@ -263,6 +976,22 @@ impl Application for App {
                 }
                 Err(e) => error!("Failed to load group icon: {e}"),
             },
+            AppMessage::GuildIconLoaded(id, handle) => match handle {
+                Ok(handle) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.update_guild(id, |g| g.icon_handle = Some(handle));
+                    }
+                }
+                Err(e) => error!("Failed to load guild icon: {e}"),
+            },
+            AppMessage::EmojiLoaded(id, handle) => match handle {
+                Ok(handle) => {
+                    if let ConnectionState::Connecetd(state, _) = &mut self.connection_state {
+                        state.emojis.insert(id, Some(handle));
+                    }
+                }
+                Err(e) => error!("Failed to load emoji: {e}"),
+            },
 
             AppMessage::ViewSelect(view) => {
                 if let ConnectionState::Connecetd(_, _) = self.connection_state {
@@ -280,29 +1009,24 @@ impl Application for App {
                 SettingsViewMessage::AccountsMessage(message) => match message {
                     AccountsMessage::AccountAdded(token) => {
                         return Command::perform(
-                            User::from_token(token.clone()),
+                            self.rest_client.with_token(token.clone()).current_user(),
                             map_result_message(|user| AppMessage::AccountLoaded(user, Some(token))),
                         )
                     }
                     AccountsMessage::AccountSelected(id) => {
-                        if id != self.settings.active_account {
+                        if id.to_string() != self.settings.active_account {
                             if let ConnectionState::Connecetd(_, gateway) =
                                 &mut self.connection_state
                             {
                                 gateway.close();
                             }
 
-                            if let Ok(token) = keyring::Entry::new(SERVICE, &id).get_password() {
-                                self.settings.active_account = id;
-                                self.connection_state = ConnectionState::Connecting;
-
-                                return Command::batch([
-                                    self.save_settings(),
-                                    Command::perform(
-                                        Gateway::new(token),
-                                        map_result_message(AppMessage::GatewayConnected),
-                                    ),
-                                ]);
+                            if let Ok(token) =
+                                keyring::Entry::new(SERVICE, &id.to_string()).get_password()
+                            {
+                                self.settings.active_account = id.to_string();
+
+                                return Command::batch([self.save_settings(), self.connect(token)]);
                             } else {
                                 self.connection_state = ConnectionState::Disconnected;
                                 error!("Keyring did not contain the token of the selected account");
@@ -310,7 +1034,7 @@ impl Application for App {
                         }
                     }
                     AccountsMessage::AccountRemoved(id) => {
-                        if self.settings.active_account == id {
+                        if self.settings.active_account == id.to_string() {
                             self.settings.active_account.clear();
 
                             if let ConnectionState::Connecetd(_, gateway) =
@@ -320,31 +1044,82 @@ impl Application for App {
                                 self.connection_state = ConnectionState::Disconnected;
                             }
                         }
-                        self.settings.accounts.retain(|a| *a != id);
+                        self.settings.accounts.retain(|a| *a != id.to_string());
                         self.accounts.retain(|a| a.id != id);
                         return self.save_settings();
                     }
                 },
             },
-            AppMessage::DirectMessagesViewMessage(_) => {}
+            AppMessage::DirectMessagesViewMessage(message) => match message {
+                PrivateChannelsViewMessage::TextChatMessage(message) => {
+                    return self.text_chat_message(message)
+                }
+                PrivateChannelsViewMessage::FriendsMessage(message) => {
+                    return self.friends_message(message)
+                }
+            },
+            AppMessage::GuildViewMessage(message) => match message {
+                GuildViewMessage::TextChatMessage(message) => {
+                    return self.text_chat_message(message)
+                }
+            },
+            AppMessage::StatusViewMessage(message) => match message {
+                StatusViewMessage::SetStatus {
+                    presence,
+                    custom_status,
+                } => return self.set_status(presence, custom_status),
+            },
         }
 
         Command::none()
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        if let ConnectionState::Connecetd(_, gateway) = &self.connection_state {
-            gateway.subscribe().map(AppMessage::GatewayEvent)
+        if let ConnectionState::Connecetd(state, gateway) = &self.connection_state {
+            let events = gateway.subscribe().map(AppMessage::GatewayEvent);
+
+            // Typing indicators expire without an event, so they are checked while any are shown
+            if state.typing.is_empty() {
+                events
+            } else {
+                Subscription::batch([
+                    events,
+                    iced::time::every(Duration::from_secs(1)).map(|_| AppMessage::TypingTick),
+                ])
+            }
         } else {
             Subscription::none()
         }
     }
 
     fn view(&self) -> Element<'_, Self::Message, Renderer<Self::Theme>> {
-        let view: Element<'_, Self::Message, Renderer<Self::Theme>> = match self.active_view {
+        let view: Element<'_, Self::Message, Renderer<Self::Theme>> = match &self.active_view {
             View::DirectMessages => {
                 if let ConnectionState::Connecetd(state, _) = &self.connection_state {
-                    private_channels_view(state, AppMessage::DirectMessagesViewMessage).into()
+                    private_channels_view(
+                        state,
+                        &self.friend_request,
+                        &self.direct_message_errors,
+                        AppMessage::DirectMessagesViewMessage,
+                    )
+                    .compact(self.settings.compact_messages)
+                    .into()
+                } else {
+                    text("Loading...").into()
+                }
+            }
+            View::Guild(id) => {
+                if let ConnectionState::Connecetd(state, _) = &self.connection_state {
+                    guild_view(*id, state, AppMessage::GuildViewMessage)
+                        .compact(self.settings.compact_messages)
+                        .into()
+                } else {
+                    text("Loading...").into()
+                }
+            }
+            View::Status => {
+                if let ConnectionState::Connecetd(state, _) = &self.connection_state {
+                    status_view(state, AppMessage::StatusViewMessage).into()
                 } else {
                     text("Loading...").into()
                 }
@@ -357,8 +1132,20 @@ impl Application for App {
             .into(),
         };
 
+        let (guilds, user): (&[Guild], _) =
+            if let ConnectionState::Connecetd(state, _) = &self.connection_state {
+                (&state.guilds[..], state.user_cache.get(&state.user_id))
+            } else {
+                (&[], None)
+            };
+
         row![
-            guildbar(self.active_view.clone(), AppMessage::ViewSelect),
+            guildbar(
+                self.active_view.clone(),
+                guilds,
+                user,
+                AppMessage::ViewSelect
+            ),
             view
         ]
         .into()
@@ -42,6 +42,8 @@ pub struct Theme {
     pub text: [f32; 3],
     pub text_weak: [f32; 3],
     pub text_selection: [f32; 3],
+    #[serde(default = "default_error")]
+    pub error: [f32; 3],
     pub spacer: [f32; 4],
     pub primary: [f32; 3],
     pub secondary: [f32; 3],
@@ -51,6 +53,17 @@ pub struct Theme {
     pub background_weak: [f32; 3],
     pub border_radius: f32,
     pub button: Button,
+    #[serde(default)]
+    pub status: Status,
+    #[serde(default)]
+    pub markdown: Markdown,
+    #[serde(default)]
+    pub highlight: Highlight,
+}
+
+/// Themes from before the error color existed use the one of the dark theme
+fn default_error() -> [f32; 3] {
+    Theme::dark().error
 }
 
 impl DefaultThemes for Theme {
@@ -59,6 +72,7 @@ impl DefaultThemes for Theme {
             text: [0.9, 0.9, 0.9],
             text_weak: [0.65, 0.65, 0.65],
             text_selection: [0.4, 0.7, 1.0],
+            error: [0.95, 0.3, 0.3],
             spacer: [1.0, 1.0, 1.0, 0.05],
             primary: [0.2, 0.5, 1.0],
             secondary: [0.4, 0.4, 0.4],
@@ -68,6 +82,9 @@ impl DefaultThemes for Theme {
             background_weak: [0.25, 0.25, 0.25],
             border_radius: 5.0,
             button: Button::dark(),
+            status: Status::dark(),
+            markdown: Markdown::dark(),
+            highlight: Highlight::dark(),
         }
     }
 
@@ -76,6 +93,7 @@ impl DefaultThemes for Theme {
             text: [0.1, 0.1, 0.1],
             text_weak: [0.5, 0.5, 0.5],
             text_selection: [0.4, 0.7, 1.0],
+            error: [0.85, 0.15, 0.15],
             spacer: [0.0, 0.0, 0.0, 0.1],
             primary: [0.2, 0.5, 1.0],
             secondary: [0.75, 0.75, 0.75],
@@ -85,6 +103,9 @@ impl DefaultThemes for Theme {
             background_weak: [0.9, 0.9, 0.9],
             border_radius: 5.0,
             button: Button::light(),
+            status: Status::light(),
+            markdown: Markdown::light(),
+            highlight: Highlight::light(),
         }
     }
 }
@@ -138,3 +159,130 @@ impl Default for Button {
         Self::dark()
     }
 }
+
+/// Colors of the presence badges on avatars
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub online: [f32; 3],
+    pub idle: [f32; 3],
+    pub do_not_disturb: [f32; 3],
+    pub offline: [f32; 3],
+}
+
+impl DefaultThemes for Status {
+    fn dark() -> Self {
+        Self {
+            online: [0.14, 0.65, 0.35],
+            idle: [0.94, 0.7, 0.2],
+            do_not_disturb: [0.94, 0.28, 0.28],
+            offline: [0.5, 0.5, 0.5],
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            online: [0.14, 0.6, 0.32],
+            idle: [0.9, 0.62, 0.1],
+            do_not_disturb: [0.88, 0.22, 0.22],
+            offline: [0.6, 0.6, 0.6],
+        }
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Colors of markdown in messages
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Markdown {
+    pub code_background: [f32; 3],
+    pub spoiler: [f32; 3],
+    pub quote_bar: [f32; 3],
+    pub mention: [f32; 3],
+    pub mention_background: [f32; 4],
+    /// Background of messages that mention the current user
+    pub mentioned_message: [f32; 4],
+}
+
+impl DefaultThemes for Markdown {
+    fn dark() -> Self {
+        Self {
+            code_background: [0.12, 0.12, 0.12],
+            spoiler: [0.1, 0.1, 0.1],
+            quote_bar: [0.4, 0.4, 0.4],
+            mention: [0.75, 0.82, 1.0],
+            mention_background: [0.35, 0.4, 0.95, 0.3],
+            mentioned_message: [0.94, 0.7, 0.2, 0.08],
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            code_background: [0.92, 0.92, 0.92],
+            spoiler: [0.25, 0.25, 0.25],
+            quote_bar: [0.75, 0.75, 0.75],
+            mention: [0.2, 0.3, 0.8],
+            mention_background: [0.35, 0.4, 0.95, 0.15],
+            mentioned_message: [0.94, 0.7, 0.2, 0.15],
+        }
+    }
+}
+
+impl Default for Markdown {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Colors of syntax highlighting in code blocks
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    pub keyword: [f32; 3],
+    pub type_name: [f32; 3],
+    pub function: [f32; 3],
+    pub string: [f32; 3],
+    pub number: [f32; 3],
+    pub comment: [f32; 3],
+    pub error: [f32; 3],
+    pub warning: [f32; 3],
+}
+
+impl DefaultThemes for Highlight {
+    fn dark() -> Self {
+        Self {
+            keyword: [0.8, 0.47, 0.87],
+            type_name: [0.9, 0.75, 0.48],
+            function: [0.38, 0.69, 0.94],
+            string: [0.6, 0.76, 0.47],
+            number: [0.82, 0.6, 0.4],
+            comment: [0.5, 0.53, 0.57],
+            error: [0.95, 0.3, 0.3],
+            warning: [0.94, 0.7, 0.2],
+        }
+    }
+
+    fn light() -> Self {
+        Self {
+            keyword: [0.65, 0.15, 0.64],
+            type_name: [0.76, 0.52, 0.0],
+            function: [0.25, 0.47, 0.95],
+            string: [0.31, 0.63, 0.31],
+            number: [0.6, 0.41, 0.0],
+            comment: [0.63, 0.63, 0.65],
+            error: [0.85, 0.15, 0.15],
+            warning: [0.8, 0.5, 0.0],
+        }
+    }
+}
+
+impl Default for Highlight {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -5,12 +5,14 @@ use iced::{
     widget::{
         button, container,
         rule::{self, FillMode},
-        svg, text, text_input,
+        svg, text, text_input, toggler,
     },
     Background, Color,
 };
 use iced_native::widget::scrollable;
 
+use crate::data::{highlight::TokenKind, user::Presence};
+
 use self::data::ThemeData;
 
 #[derive(Default)]
@@ -224,6 +226,20 @@ pub enum Container {
 
     /// color, border radius
     Color(Color, f32),
+
+    /// presence, border radius
+    Status(Presence, f32),
+
+    /// border radius
+    Code(f32),
+    /// revealed
+    Spoiler(bool),
+    /// Left border of block quotes
+    QuoteBar,
+    /// Background of a mention, uses the color of the role if there is one
+    Mention(Option<Color>),
+    /// Message that mentions the current user
+    Mentioned,
 }
 
 impl container::StyleSheet for Theme {
@@ -259,6 +275,55 @@ impl container::StyleSheet for Theme {
                 appearance.background = Some(Background::Color(*color));
                 appearance.border_radius = *border_radius;
             }
+            Container::Status(presence, border_radius) => {
+                let status = &self.data.theme.status;
+                let color = match presence {
+                    Presence::Online => status.online,
+                    Presence::Idle => status.idle,
+                    Presence::DoNotDisturb => status.do_not_disturb,
+                    Presence::Offline => status.offline,
+                };
+
+                // The border separates the badge from the avatar below it
+                appearance.background = Some(Background::Color(Color::from(color)));
+                appearance.border_radius = *border_radius;
+                appearance.border_width = 2.0;
+                appearance.border_color = Color::from(self.data.theme.background_strong1);
+            }
+            Container::Code(border_radius) => {
+                appearance.background = Some(Background::Color(Color::from(
+                    self.data.theme.markdown.code_background,
+                )));
+                appearance.border_radius = *border_radius;
+            }
+            Container::Spoiler(revealed) => {
+                let mut color = Color::from(self.data.theme.markdown.spoiler);
+                if *revealed {
+                    color.a = 0.2;
+                }
+                appearance.background = Some(Background::Color(color));
+                appearance.border_radius = 3.0;
+            }
+            Container::QuoteBar => {
+                appearance.background = Some(Background::Color(Color::from(
+                    self.data.theme.markdown.quote_bar,
+                )));
+                appearance.border_radius = 2.0;
+            }
+            Container::Mention(color) => {
+                let color = match color {
+                    Some(color) => Color { a: 0.15, ..*color },
+                    None => Color::from(self.data.theme.markdown.mention_background),
+                };
+                appearance.background = Some(Background::Color(color));
+                appearance.border_radius = 3.0;
+            }
+            Container::Mentioned => {
+                appearance.background = Some(Background::Color(Color::from(
+                    self.data.theme.markdown.mentioned_message,
+                )));
+                appearance.border_radius = self.data.theme.border_radius;
+            }
             _ => {}
         }
 
@@ -352,12 +417,18 @@ impl svg::StyleSheet for Theme {
     }
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, PartialEq)]
 pub enum Text {
     #[default]
     Default,
     Weak,
+    Error,
     Color(Color),
+
+    Mention,
+
+    /// Syntax highlighting of code
+    Highlight(TokenKind),
 }
 
 impl text::StyleSheet for Theme {
@@ -369,7 +440,31 @@ impl text::StyleSheet for Theme {
             Text::Weak => text::Appearance {
                 color: Some(Color::from(self.data.theme.text_weak)),
             },
+            Text::Error => text::Appearance {
+                color: Some(Color::from(self.data.theme.error)),
+            },
             Text::Color(color) => text::Appearance { color: Some(color) },
+            Text::Mention => text::Appearance {
+                color: Some(Color::from(self.data.theme.markdown.mention)),
+            },
+            Text::Highlight(kind) => {
+                let highlight = &self.data.theme.highlight;
+                let color = match kind {
+                    TokenKind::Plain => return Default::default(),
+                    TokenKind::Keyword => highlight.keyword,
+                    TokenKind::Type => highlight.type_name,
+                    TokenKind::Function => highlight.function,
+                    TokenKind::String => highlight.string,
+                    TokenKind::Number => highlight.number,
+                    TokenKind::Comment => highlight.comment,
+                    TokenKind::Error => highlight.error,
+                    TokenKind::Warning => highlight.warning,
+                };
+
+                text::Appearance {
+                    color: Some(Color::from(color)),
+                }
+            }
         }
     }
 }
@@ -411,3 +506,30 @@ impl text_input::StyleSheet for Theme {
         Color::from(self.data.theme.text_selection)
     }
 }
+
+impl toggler::StyleSheet for Theme {
+    type Style = ();
+
+    fn active(&self, _style: &Self::Style, is_active: bool) -> toggler::Appearance {
+        toggler::Appearance {
+            background: if is_active {
+                Color::from(self.data.theme.primary)
+            } else {
+                Color::from(self.data.theme.secondary)
+            },
+            background_border: None,
+            foreground: Color::from(self.data.theme.text),
+            foreground_border: None,
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style, is_active: bool) -> toggler::Appearance {
+        let mut appearance = self.active(style, is_active);
+        if is_active {
+            appearance.background = Color::from(self.data.theme.button.primary_hovered);
+        } else {
+            appearance.background = Color::from(self.data.theme.button.secondary_hovered);
+        }
+        appearance
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{HashMap, HashSet};
+
+use iced::{
+    widget::{container, text},
+    Element, Length,
+};
+use iced_graphics::Renderer;
+use iced_lazy::Component;
+use iced_native::row;
+
+use crate::{
+    data::{
+        guild::{Guild, GuildChannel, GuildChannelKind},
+        permissions::{own_channel_permissions, Permissions},
+        snowflake::Snowflake,
+        state::State,
+    },
+    gui::{
+        components::{
+            sidebar::{sidebar, SidebarEntryType},
+            text_chat::{text_chat, TextChatMessage},
+        },
+        theme::{Text, Theme},
+    },
+};
+
+pub fn guild_view<'a, Message>(
+    guild_id: Snowflake,
+    state: &'a State,
+    on_message: impl Fn(GuildViewMessage) -> Message + 'static,
+) -> GuildView<'a, Message> {
+    GuildView::new(guild_id, state, on_message)
+}
+
+#[derive(Debug, Clone)]
+pub enum GuildViewMessage {
+    TextChatMessage(TextChatMessage),
+}
+
+#[derive(Default)]
+pub struct GuildState {
+    /// Opened channel of each guild
+    active_channels: HashMap<Snowflake, Snowflake>,
+    collapsed_categories: HashSet<Snowflake>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    EntrySelected(SidebarEntryType<()>),
+    TextChatMessage(TextChatMessage),
+}
+
+pub struct GuildView<'a, Message> {
+    guild_id: Snowflake,
+    state: &'a State,
+    compact: bool,
+    on_message: Box<dyn Fn(GuildViewMessage) -> Message>,
+}
+
+impl<'a, Message> GuildView<'a, Message> {
+    fn new(
+        guild_id: Snowflake,
+        state: &'a State,
+        on_message: impl Fn(GuildViewMessage) -> Message + 'static,
+    ) -> Self {
+        Self {
+            guild_id,
+            state,
+            compact: false,
+            on_message: Box::new(on_message),
+        }
+    }
+
+    /// Shows messages in the compact layout
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+}
+
+impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>> for GuildView<'a, Message>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    type State = GuildState;
+    type Event = Event;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            Event::EntrySelected(entry_type) => match entry_type {
+                SidebarEntryType::Category(category, _) => {
+                    if !state.collapsed_categories.remove(&category.id) {
+                        state.collapsed_categories.insert(category.id);
+                    }
+                    None
+                }
+                SidebarEntryType::GuildChannel(channel) if entry_type.is_selectable() => {
+                    state.active_channels.insert(self.guild_id, channel.id);
+
+                    // Fetch the latest messages of the opened channel
+                    Some((self.on_message)(GuildViewMessage::TextChatMessage(
+                        TextChatMessage::LoadHistory {
+                            channel_id: channel.id,
+                            before: None,
+                        },
+                    )))
+                }
+                _ => None,
+            },
+            Event::TextChatMessage(message) => Some((self.on_message)(
+                GuildViewMessage::TextChatMessage(message),
+            )),
+        }
+    }
+
+    fn view(
+        &self,
+        state: &Self::State,
+    ) -> iced_native::Element<'_, Self::Event, Renderer<Backend, Theme>> {
+        let guild = match self.state.guild(self.guild_id) {
+            Some(guild) => guild,
+            None => return text("Guild not found").into(),
+        };
+
+        let active_channel = state
+            .active_channels
+            .get(&self.guild_id)
+            .and_then(|id| guild.channels.iter().find(|c| c.id == *id));
+
+        let entries = channel_entries(
+            guild,
+            self.state.user_id,
+            &state.collapsed_categories,
+            active_channel,
+        );
+        let sidebar = sidebar(&entries, Event::EntrySelected)
+            .selected(active_channel.cloned().map(SidebarEntryType::GuildChannel));
+
+        let content: Element<_, _> = if let Some(channel) = active_channel {
+            text_chat(channel.id, &self.state, Event::TextChatMessage)
+                .permissions(own_channel_permissions(guild, channel, self.state.user_id))
+                .compact(self.compact)
+                .into()
+        } else {
+            container(text("Select a channel").style(Text::Weak))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into()
+        };
+
+        row![sidebar, content].into()
+    }
+}
+
+impl<'a, Message, Backend> From<GuildView<'a, Message>>
+    for Element<'a, Message, Renderer<Backend, Theme>>
+where
+    Message: 'a,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    fn from(guild_view: GuildView<'a, Message>) -> Self {
+        iced_lazy::component(guild_view)
+    }
+}
+
+/// Channels without a category come first, followed by the categories and their channels.
+/// Like in the discord client, voice channels are sorted below the text channels of their group
+fn channel_entries(
+    guild: &Guild,
+    user_id: Snowflake,
+    collapsed_categories: &HashSet<Snowflake>,
+    active_channel: Option<&GuildChannel>,
+) -> Vec<SidebarEntryType<()>> {
+    let sort_key = |c: &&GuildChannel| {
+        (
+            matches!(c.kind, GuildChannelKind::Voice | GuildChannelKind::Stage),
+            c.position,
+            c.id,
+        )
+    };
+
+    let can_view = |c: &GuildChannel| {
+        own_channel_permissions(guild, c, user_id).contains(Permissions::VIEW_CHANNEL)
+    };
+
+    let channels = |parent_id: Option<Snowflake>| {
+        let mut channels = guild
+            .channels
+            .iter()
+            .filter(|c| {
+                c.parent_id == parent_id
+                    && matches!(
+                        c.kind,
+                        GuildChannelKind::Text
+                            | GuildChannelKind::Announcement
+                            | GuildChannelKind::Voice
+                            | GuildChannelKind::Stage
+                            | GuildChannelKind::Forum
+                    )
+                    && can_view(*c)
+            })
+            .collect::<Vec<_>>();
+        channels.sort_by_key(sort_key);
+        channels
+    };
+
+    let mut categories = guild
+        .channels
+        .iter()
+        .filter(|c| c.kind == GuildChannelKind::Category)
+        .collect::<Vec<_>>();
+    categories.sort_by_key(|c| (c.position, c.id));
+
+    let mut entries = channels(None)
+        .into_iter()
+        .map(|c| SidebarEntryType::GuildChannel(c.clone()))
+        .collect::<Vec<_>>();
+
+    for category in categories {
+        // Categories are hidden if none of their channels are visible.
+        // Empty categories are shown if the category itself can be viewed
+        let category_channels = channels(Some(category.id));
+        let empty = !guild
+            .channels
+            .iter()
+            .any(|c| c.parent_id == Some(category.id));
+        if category_channels.is_empty() && !(empty && can_view(category)) {
+            continue;
+        }
+
+        let collapsed = collapsed_categories.contains(&category.id);
+        entries.push(SidebarEntryType::Category(category.clone(), collapsed));
+
+        // The opened channel stays visible in a collapsed category
+        entries.extend(
+            category_channels
+                .into_iter()
+                .filter(|c| !collapsed || Some(*c) == active_channel)
+                .map(|c| SidebarEntryType::GuildChannel(c.clone())),
+        );
+    }
+
+    entries
+}
@@ -1,2 +1,4 @@
+pub mod guild;
 pub mod private_channels;
 pub mod settings;
+pub mod status;
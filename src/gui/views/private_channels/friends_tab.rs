@@ -0,0 +1,392 @@
+use iced::widget::scrollable::Properties;
+use iced::{
+    alignment::Vertical,
+    widget::{button, container, horizontal_space, scrollable, text, text_input, Column, Row},
+    Alignment, Element, Length,
+};
+use iced_graphics::Renderer;
+use iced_lazy::Component;
+use iced_native::{column, row};
+
+use crate::{
+    data::{
+        snowflake::Snowflake,
+        state::{Relationship, RelationshipKind, State as AppState},
+        user::{Presence, User},
+    },
+    gui::{
+        components::images::user_avatar,
+        theme::{Button, Text, Theme},
+    },
+};
+
+fn action_button<'a, Backend>(
+    label: &str,
+    style: Button,
+    event: Event,
+) -> Element<'a, Event, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
+{
+    button(text(label).size(16))
+        .style(style)
+        .padding([5, 12])
+        .on_press(event)
+        .into()
+}
+
+fn friend_row<'a, Backend>(
+    relationship: &Relationship,
+    user: &User,
+) -> Element<'a, Event, Renderer<Backend, Theme>>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    let (status, actions) = match relationship.kind {
+        RelationshipKind::Friend => (
+            user.custom_status()
+                .unwrap_or_else(|| user.presence.to_string()),
+            vec![
+                action_button(
+                    "Message",
+                    Button::Primary(None),
+                    Event::MessagePressed(user.id),
+                ),
+                action_button(
+                    "Remove",
+                    Button::Secondary(None),
+                    Event::RemovePressed(user.id),
+                ),
+                action_button(
+                    "Block",
+                    Button::Secondary(None),
+                    Event::BlockPressed(user.id),
+                ),
+            ],
+        ),
+        RelationshipKind::PendingIncoming => (
+            String::from("Incoming Friend Request"),
+            vec![
+                action_button(
+                    "Accept",
+                    Button::Primary(None),
+                    Event::AcceptPressed(user.id),
+                ),
+                action_button(
+                    "Ignore",
+                    Button::Secondary(None),
+                    Event::RemovePressed(user.id),
+                ),
+                action_button(
+                    "Block",
+                    Button::Secondary(None),
+                    Event::BlockPressed(user.id),
+                ),
+            ],
+        ),
+        RelationshipKind::PendingOutgoing => (
+            String::from("Outgoing Friend Request"),
+            vec![action_button(
+                "Cancel",
+                Button::Secondary(None),
+                Event::RemovePressed(user.id),
+            )],
+        ),
+        RelationshipKind::Blocked => (
+            String::from("Blocked"),
+            vec![action_button(
+                "Unblock",
+                Button::Secondary(None),
+                Event::RemovePressed(user.id),
+            )],
+        ),
+        _ => (String::new(), vec![]),
+    };
+
+    // Users that migrated to unique usernames have no discriminator
+    let mut name = row![text(&user.username)];
+    if user.discriminator != 0 {
+        name = name.push(text(format!("#{:04}", user.discriminator)).style(Text::Weak));
+    }
+
+    container(
+        row![
+            user_avatar(user, 35, relationship.kind == RelationshipKind::Friend),
+            column![name, text(status).size(14).style(Text::Weak)],
+            horizontal_space(Length::Fill),
+            Row::with_children(actions).spacing(10)
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    )
+    .width(Length::Fill)
+    .padding([8, 10])
+    .align_y(Vertical::Center)
+    .into()
+}
+
+pub fn friends_tab<'a, Message>(
+    state: &'a AppState,
+    friend_request: &'a FriendRequest,
+    on_message: impl Fn(FriendsMessage) -> Message + 'static,
+) -> FriendsTab<'a, Message> {
+    FriendsTab::new(state, friend_request, on_message)
+}
+
+/// Friend request form of the friends tab. The input is kept until the request
+/// was sent so it can be corrected if sending failed
+#[derive(Default, Debug, Clone)]
+pub struct FriendRequest {
+    /// Username of the user to send a friend request to
+    pub input: String,
+    pub sending: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum FriendsMessage {
+    /// Opens the direct message channel with a user, creating it if necessary
+    OpenDirectMessage(Snowflake),
+    /// Username typed into the friend request form
+    FriendRequestChanged(String),
+    SendFriendRequest {
+        username: String,
+        discriminator: Option<u16>,
+    },
+    AcceptRequest(Snowflake),
+    Block(Snowflake),
+    /// Removes a friend, ignores or cancels a friend request or unblocks a user
+    RemoveRelationship(Snowflake),
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    #[default]
+    Online,
+    All,
+    Pending,
+    Blocked,
+}
+
+impl Filter {
+    const ALL: [Self; 4] = [Self::Online, Self::All, Self::Pending, Self::Blocked];
+
+    fn matches(self, relationship: &Relationship, user: &User) -> bool {
+        match self {
+            Filter::Online => {
+                relationship.kind == RelationshipKind::Friend
+                    && !matches!(user.presence, Presence::Offline)
+            }
+            Filter::All => relationship.kind == RelationshipKind::Friend,
+            Filter::Pending => matches!(
+                relationship.kind,
+                RelationshipKind::PendingIncoming | RelationshipKind::PendingOutgoing
+            ),
+            Filter::Blocked => relationship.kind == RelationshipKind::Blocked,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct State {
+    filter: Filter,
+    search: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    FilterSelected(Filter),
+    SearchChanged(String),
+    FriendRequestChanged(String),
+    SendRequestPressed,
+    MessagePressed(Snowflake),
+    AcceptPressed(Snowflake),
+    RemovePressed(Snowflake),
+    BlockPressed(Snowflake),
+}
+
+pub struct FriendsTab<'a, Message> {
+    state: &'a AppState,
+    friend_request: &'a FriendRequest,
+    on_message: Box<dyn Fn(FriendsMessage) -> Message>,
+}
+
+impl<'a, Message> FriendsTab<'a, Message> {
+    fn new(
+        state: &'a AppState,
+        friend_request: &'a FriendRequest,
+        on_message: impl Fn(FriendsMessage) -> Message + 'static,
+    ) -> Self {
+        Self {
+            state,
+            friend_request,
+            on_message: Box::new(on_message),
+        }
+    }
+}
+
+impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>> for FriendsTab<'a, Message>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    type State = State;
+    type Event = Event;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            Event::FilterSelected(filter) => {
+                state.filter = filter;
+                None
+            }
+            Event::SearchChanged(search) => {
+                state.search = search;
+                None
+            }
+            Event::FriendRequestChanged(input) => Some((self.on_message)(
+                FriendsMessage::FriendRequestChanged(input),
+            )),
+            Event::SendRequestPressed => {
+                let input = self.friend_request.input.trim();
+
+                // Users that did not migrate to unique usernames are added as name#1234
+                let (username, discriminator) = match input
+                    .rsplit_once('#')
+                    .and_then(|(name, d)| Some((name, d.parse::<u16>().ok()?)))
+                {
+                    Some((name, discriminator)) => (name.to_string(), Some(discriminator)),
+                    None => (input.to_string(), None),
+                };
+
+                if username.is_empty() || self.friend_request.sending {
+                    None
+                } else {
+                    Some((self.on_message)(FriendsMessage::SendFriendRequest {
+                        username,
+                        discriminator,
+                    }))
+                }
+            }
+            Event::MessagePressed(id) => {
+                Some((self.on_message)(FriendsMessage::OpenDirectMessage(id)))
+            }
+            Event::AcceptPressed(id) => Some((self.on_message)(FriendsMessage::AcceptRequest(id))),
+            Event::RemovePressed(id) => {
+                Some((self.on_message)(FriendsMessage::RemoveRelationship(id)))
+            }
+            Event::BlockPressed(id) => Some((self.on_message)(FriendsMessage::Block(id))),
+        }
+    }
+
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event, Renderer<Backend, Theme>> {
+        let filters = Row::with_children(
+            Filter::ALL
+                .into_iter()
+                .map(|filter| {
+                    button(text(format!("{filter:?}")))
+                        .style(Button::TransparentHover(filter == state.filter, Some(5.0)))
+                        .padding([5, 12])
+                        .on_press(Event::FilterSelected(filter))
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let search = state.search.to_lowercase();
+        let mut friends = self
+            .state
+            .relationships
+            .iter()
+            .filter_map(|r| Some((r, self.state.user_cache.get(&r.id)?)))
+            .filter(|(r, user)| {
+                state.filter.matches(r, user) && user.username.to_lowercase().contains(&search)
+            })
+            .collect::<Vec<_>>();
+        friends.sort_by_key(|(_, user)| user.username.to_lowercase());
+
+        let list: Element<_, _> = if friends.is_empty() {
+            text("No one here yet").style(Text::Weak).into()
+        } else {
+            scrollable(
+                Column::with_children(
+                    friends
+                        .iter()
+                        .map(|(r, user)| friend_row(r, user))
+                        .collect(),
+                )
+                .spacing(5),
+            )
+            .vertical_scroll(Properties::new().width(5).scroller_width(5))
+            .height(Length::Fill)
+            .into()
+        };
+
+        let friend_request = self.friend_request;
+        let add_friend = {
+            let mut send_button = button("Send Friend Request").padding([10, 25]);
+            if !friend_request.input.trim().is_empty() && !friend_request.sending {
+                send_button = send_button.on_press(Event::SendRequestPressed);
+            }
+
+            let form = row![
+                text_input(
+                    "Add a friend by their username",
+                    &friend_request.input,
+                    Event::FriendRequestChanged,
+                )
+                .on_submit(Event::SendRequestPressed)
+                .padding(10),
+                send_button
+            ]
+            .spacing(10);
+
+            match &friend_request.error {
+                Some(error) => column![
+                    form,
+                    text(format!("Failed to send the friend request: {error}"))
+                        .size(14)
+                        .style(Text::Error)
+                ]
+                .spacing(5),
+                None => column![form],
+            }
+        };
+
+        column![
+            filters,
+            add_friend,
+            text_input("Search", &state.search, Event::SearchChanged).padding(10),
+            text(format!("{:?} - {}", state.filter, friends.len()).to_uppercase())
+                .size(14)
+                .style(Text::Weak),
+            list
+        ]
+        .width(Length::Fill)
+        .spacing(15)
+        .padding(15)
+        .into()
+    }
+}
+
+impl<'a, Message, Backend> From<FriendsTab<'a, Message>>
+    for Element<'a, Message, Renderer<Backend, Theme>>
+where
+    Message: 'a,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    fn from(friends_tab: FriendsTab<'a, Message>) -> Self {
+        iced_lazy::component(friends_tab)
+    }
+}
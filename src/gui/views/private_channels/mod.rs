@@ -1,36 +1,55 @@
-use iced::{widget::text, Element};
+mod friends_tab;
+
+pub use self::friends_tab::{FriendRequest, FriendsMessage};
+
+use std::collections::HashMap;
+
+use iced::{
+    widget::{button, container, text},
+    Alignment, Element, Length,
+};
 use iced_graphics::Renderer;
 use iced_lazy::Component;
-use iced_native::row;
+use iced_native::{column, row};
 
 use crate::{
-    data::state::{PrivateChannel, PrivateChannelKind, State},
+    data::{
+        snowflake::Snowflake,
+        state::{PrivateChannel, PrivateChannelKind, State},
+    },
     gui::{
         components::{
             sidebar::{sidebar, SidebarEntryType},
             text_chat::{text_chat, TextChatMessage},
         },
-        theme::Theme,
+        theme::{Button, Text, Theme},
     },
 };
 
+use self::friends_tab::friends_tab;
+
 pub fn private_channels_view<'a, Message>(
     state: &'a State,
+    friend_request: &'a FriendRequest,
+    direct_message_errors: &'a HashMap<Snowflake, String>,
     on_message: impl Fn(PrivateChannelsViewMessage) -> Message + 'static,
 ) -> PrivateChannelsView<'a, Message> {
-    PrivateChannelsView::new(state, on_message)
+    PrivateChannelsView::new(state, friend_request, direct_message_errors, on_message)
 }
 
 #[derive(Default, Debug, Clone)]
 pub enum Tab {
     #[default]
     Friends,
-    Channel(String),
+    Channel(Snowflake),
+    /// Direct messages with a user whose channel might not have been created yet
+    DirectMessage(Snowflake),
 }
 
 #[derive(Debug, Clone)]
 pub enum PrivateChannelsViewMessage {
-    Default,
+    TextChatMessage(TextChatMessage),
+    FriendsMessage(FriendsMessage),
 }
 
 #[derive(Default)]
@@ -42,23 +61,39 @@ pub struct PrivateChannelsState {
 pub enum Event {
     TabSelected(SidebarEntryType<()>),
     TextChatMessage(TextChatMessage),
+    FriendsMessage(FriendsMessage),
 }
 
 pub struct PrivateChannelsView<'a, Message> {
     state: &'a State,
+    friend_request: &'a FriendRequest,
+    /// Why the direct message channel with a user could not be opened
+    direct_message_errors: &'a HashMap<Snowflake, String>,
+    compact: bool,
     on_message: Box<dyn Fn(PrivateChannelsViewMessage) -> Message>,
 }
 
 impl<'a, Message> PrivateChannelsView<'a, Message> {
     fn new(
         state: &'a State,
+        friend_request: &'a FriendRequest,
+        direct_message_errors: &'a HashMap<Snowflake, String>,
         on_message: impl Fn(PrivateChannelsViewMessage) -> Message + 'static,
     ) -> Self {
         Self {
             state,
+            friend_request,
+            direct_message_errors,
+            compact: false,
             on_message: Box::new(on_message),
         }
     }
+
+    /// Shows messages in the compact layout
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 }
 
 impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>>
@@ -77,51 +112,132 @@ where
         match event {
             Event::TabSelected(entry_type) => match entry_type {
                 SidebarEntryType::PrivateChannel(PrivateChannel { id, .. }, _) => {
-                    state.active_tab = Tab::Channel(id)
+                    state.active_tab = Tab::Channel(id);
+
+                    // Fetch the latest messages of the opened channel
+                    Some((self.on_message)(
+                        PrivateChannelsViewMessage::TextChatMessage(TextChatMessage::LoadHistory {
+                            channel_id: id,
+                            before: None,
+                        }),
+                    ))
+                }
+                _ => {
+                    state.active_tab = Tab::Friends;
+                    None
                 }
-                _ => state.active_tab = Tab::Friends,
             },
-            Event::TextChatMessage(_) => {}
+            Event::TextChatMessage(message) => Some((self.on_message)(
+                PrivateChannelsViewMessage::TextChatMessage(message),
+            )),
+            Event::FriendsMessage(FriendsMessage::OpenDirectMessage(user_id)) => {
+                if let Some(channel) = self.state.direct_message_channel(user_id) {
+                    state.active_tab = Tab::Channel(channel.id);
+
+                    Some((self.on_message)(
+                        PrivateChannelsViewMessage::TextChatMessage(TextChatMessage::LoadHistory {
+                            channel_id: channel.id,
+                            before: None,
+                        }),
+                    ))
+                } else {
+                    // The channel is shown once it was created
+                    state.active_tab = Tab::DirectMessage(user_id);
+
+                    Some((self.on_message)(
+                        PrivateChannelsViewMessage::FriendsMessage(
+                            FriendsMessage::OpenDirectMessage(user_id),
+                        ),
+                    ))
+                }
+            }
+            Event::FriendsMessage(message) => Some((self.on_message)(
+                PrivateChannelsViewMessage::FriendsMessage(message),
+            )),
         }
-        None
     }
 
     fn view(
         &self,
         state: &Self::State,
     ) -> iced_native::Element<'_, Self::Event, Renderer<Backend, Theme>> {
-        let sidebar = sidebar(
-            &[
-                vec![
-                    SidebarEntryType::Button((), String::from("Friends")),
-                    SidebarEntryType::Spacer,
-                ],
-                self.state
-                    .private_channels
-                    .iter()
-                    .flat_map(|c| match c.kind {
-                        PrivateChannelKind::DirectMessage => {
-                            Some(SidebarEntryType::PrivateChannel(
-                                c.clone(),
-                                self.state
-                                    .user_cache
-                                    .get(c.recipients.first().unwrap_or(&String::from("")))
-                                    .cloned(),
-                            ))
-                        }
-                        PrivateChannelKind::Group => {
-                            Some(SidebarEntryType::PrivateChannel(c.clone(), None))
-                        }
-                    })
-                    .collect::<Vec<_>>(),
-            ]
-            .concat(),
-            Event::TabSelected,
-        );
-
-        let content: Element<_, _> = match &state.active_tab {
-            Tab::Friends => text("Friends...").into(),
-            Tab::Channel(id) => text_chat(id.clone(), &self.state, Event::TextChatMessage).into(),
+        let active_channel = match state.active_tab {
+            Tab::Friends => None,
+            Tab::Channel(id) => Some(id),
+            Tab::DirectMessage(user_id) => self.state.direct_message_channel(user_id).map(|c| c.id),
+        };
+
+        let entries = [
+            vec![
+                SidebarEntryType::Button((), String::from("Friends")),
+                SidebarEntryType::Spacer,
+            ],
+            self.state
+                .private_channels
+                .iter()
+                .flat_map(|c| match c.kind {
+                    PrivateChannelKind::DirectMessage => Some(SidebarEntryType::PrivateChannel(
+                        c.clone(),
+                        c.recipients
+                            .first()
+                            .and_then(|id| self.state.user_cache.get(id))
+                            .cloned(),
+                    )),
+                    PrivateChannelKind::Group => {
+                        Some(SidebarEntryType::PrivateChannel(c.clone(), None))
+                    }
+                })
+                .collect::<Vec<_>>(),
+        ]
+        .concat();
+
+        let selected = match active_channel {
+            Some(id) => entries
+                .iter()
+                .find(|e| matches!(e, SidebarEntryType::PrivateChannel(c, _) if c.id == id))
+                .cloned(),
+            None => entries.first().cloned(),
+        };
+        let sidebar = sidebar(&entries, Event::TabSelected).selected(selected);
+
+        let content: Element<_, _> = match (&state.active_tab, active_channel) {
+            (Tab::Friends, _) => {
+                friends_tab(self.state, self.friend_request, Event::FriendsMessage).into()
+            }
+            (_, Some(id)) => text_chat(id, &self.state, Event::TextChatMessage)
+                .compact(self.compact)
+                .into(),
+            (Tab::DirectMessage(user_id), None)
+                if self.direct_message_errors.contains_key(user_id) =>
+            {
+                let error = &self.direct_message_errors[user_id];
+
+                container(
+                    column![
+                        text(format!("Failed to open the conversation: {error}"))
+                            .style(Text::Error),
+                        button(text("Retry").size(16))
+                            .style(Button::Secondary(None))
+                            .padding([5, 12])
+                            .on_press(Event::FriendsMessage(FriendsMessage::OpenDirectMessage(
+                                *user_id
+                            )))
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into()
+            }
+            (_, None) => container(text("Opening conversation...").style(Text::Weak))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+                .center_y()
+                .into(),
         };
 
         row![sidebar, content].into()
@@ -8,7 +8,7 @@ use iced_lazy::Component;
 use iced_native::{column, row};
 
 use crate::{
-    data::user::User,
+    data::{snowflake::Snowflake, user::User},
     gui::{
         components::{empty, images::user_avatar},
         icons,
@@ -33,7 +33,7 @@ where
             .width(Length::Units(25))
             .height(Length::Units(25))
             .padding(4)
-            .on_press(Event::AccountRemoved(account.id.clone()));
+            .on_press(Event::AccountRemoved(account.id));
 
         container(row![
             text(&account.username),
@@ -45,18 +45,18 @@ where
         .align_y(Vertical::Center)
     };
 
-    button(row![user_avatar(account, 40), content].spacing(15))
+    button(row![user_avatar(account, 40, false), content].spacing(15))
         .style(Button::Border(selected, Some(15.0), 5.0))
         .width(Length::Fill)
         .height(Length::Units(70))
         .padding([15, 20])
-        .on_press(Event::AccountSelected(account.id.clone()))
+        .on_press(Event::AccountSelected(account.id))
         .into()
 }
 
 pub fn accounts_tab<'a, Message>(
     accounts: &'a [User],
-    active_account: String,
+    active_account: Option<Snowflake>,
     on_message: impl Fn(AccountsMessage) -> Message + 'static,
 ) -> AccountsTab<'a, Message> {
     AccountsTab::new(accounts, active_account, on_message)
@@ -65,8 +65,8 @@ pub fn accounts_tab<'a, Message>(
 #[derive(Debug, Clone)]
 pub enum AccountsMessage {
     AccountAdded(String),
-    AccountSelected(String),
-    AccountRemoved(String),
+    AccountSelected(Snowflake),
+    AccountRemoved(Snowflake),
 }
 
 #[derive(Default)]
@@ -76,22 +76,22 @@ pub struct State {
 
 #[derive(Debug, Clone)]
 pub enum Event {
-    AccountSelected(String),
-    AccountRemoved(String),
+    AccountSelected(Snowflake),
+    AccountRemoved(Snowflake),
     TokenChanged(String),
     AddPrssed,
 }
 
 pub struct AccountsTab<'a, Message> {
     accounts: &'a [User],
-    active_account: String,
+    active_account: Option<Snowflake>,
     on_message: Box<dyn Fn(AccountsMessage) -> Message>,
 }
 
 impl<'a, Message> AccountsTab<'a, Message> {
     fn new(
         accounts: &'a [User],
-        active_account: String,
+        active_account: Option<Snowflake>,
         on_message: impl Fn(AccountsMessage) -> Message + 'static,
     ) -> Self {
         Self {
@@ -136,20 +136,23 @@ where
     }
 
     fn view(&self, state: &Self::State) -> Element<'_, Self::Event, Renderer<Backend, Theme>> {
-        let current_account: Element<_, _> =
-            if let Some(account) = self.accounts.iter().find(|a| a.id == self.active_account) {
-                column![text("Current Account"), account_button(account, true)]
-                    .spacing(15)
-                    .into()
-            } else {
-                empty().into()
-            };
+        let current_account: Element<_, _> = if let Some(account) = self
+            .accounts
+            .iter()
+            .find(|a| Some(a.id) == self.active_account)
+        {
+            column![text("Current Account"), account_button(account, true)]
+                .spacing(15)
+                .into()
+        } else {
+            empty().into()
+        };
 
         let accounts: Element<_, _> = {
             let accounts = self
                 .accounts
                 .iter()
-                .filter(|a| a.id != self.active_account)
+                .filter(|a| Some(a.id) != self.active_account)
                 .collect::<Vec<_>>();
 
             if accounts.len() > 0 {
@@ -1,14 +1,17 @@
 use iced::{
     alignment::{Horizontal, Vertical},
-    widget::{button, container, text, vertical_space},
+    widget::{button, container, text, toggler, vertical_space},
     Color, Element, Length,
 };
 use iced_graphics::Renderer;
 use iced_native::{column, row};
 
-use crate::gui::theme::{
-    data::{DefaultThemes, ThemeData},
-    Button, Container, Text, Theme,
+use crate::{
+    data::settings::Settings,
+    gui::theme::{
+        data::{DefaultThemes, ThemeData},
+        Button, Container, Text, Theme,
+    },
 };
 
 use super::Event;
@@ -53,7 +56,7 @@ where
 }
 
 pub fn appearance_tab<'a, Backend>(
-    active_theme: &str,
+    settings: &Settings,
 ) -> Element<'a, Event, Renderer<Backend, Theme>>
 where
     Backend: iced_graphics::Backend + iced_graphics::backend::Text + 'static,
@@ -63,13 +66,38 @@ where
 
     let default_themes = container(
         row![
-            theme_button(dark.id == active_theme, dark),
-            theme_button(light.id == active_theme, light)
+            theme_button(dark.id == settings.theme, dark),
+            theme_button(light.id == settings.theme, light)
         ]
         .spacing(5),
     )
     .style(Container::BackgroundWeak(20.0))
     .padding(12);
 
-    column![text("Theme"), default_themes].spacing(15).into()
+    let messages = container(
+        column![
+            toggler(
+                String::from("Show deleted messages"),
+                settings.show_deleted_messages,
+                Event::ShowDeletedMessagesToggled,
+            ),
+            toggler(
+                String::from("Send typing indicator"),
+                settings.send_typing_indicator,
+                Event::SendTypingIndicatorToggled,
+            ),
+            toggler(
+                String::from("Compact message display"),
+                settings.compact_messages,
+                Event::CompactMessagesToggled,
+            )
+        ]
+        .spacing(15),
+    )
+    .style(Container::BackgroundWeak(20.0))
+    .padding(20);
+
+    column![text("Theme"), default_themes, text("Messages"), messages]
+        .spacing(15)
+        .into()
 }
@@ -68,6 +68,9 @@ pub enum Event {
     TabSelected(SidebarEntryType<Tab>),
     AccountsMessage(AccountsMessage),
     ThemeSelected(String),
+    ShowDeletedMessagesToggled(bool),
+    SendTypingIndicatorToggled(bool),
+    CompactMessagesToggled(bool),
 }
 
 pub struct SettingsView<'a, Message> {
@@ -118,6 +121,27 @@ where
                     settings,
                 )))
             }
+            Event::ShowDeletedMessagesToggled(show) => {
+                let mut settings = self.settings.clone();
+                settings.show_deleted_messages = show;
+                Some((self.on_message)(SettingsViewMessage::SettingsChanged(
+                    settings,
+                )))
+            }
+            Event::SendTypingIndicatorToggled(send) => {
+                let mut settings = self.settings.clone();
+                settings.send_typing_indicator = send;
+                Some((self.on_message)(SettingsViewMessage::SettingsChanged(
+                    settings,
+                )))
+            }
+            Event::CompactMessagesToggled(compact) => {
+                let mut settings = self.settings.clone();
+                settings.compact_messages = compact;
+                Some((self.on_message)(SettingsViewMessage::SettingsChanged(
+                    settings,
+                )))
+            }
             _ => None,
         }
     }
@@ -128,11 +152,11 @@ where
         let tab: Element<_, _> = match state.active_tab {
             Tab::Accounts => accounts_tab(
                 self.accounts,
-                self.settings.active_account.clone(),
+                self.settings.active_account.parse().ok(),
                 Event::AccountsMessage,
             )
             .into(),
-            Tab::Appearance => appearance_tab(&self.settings.theme),
+            Tab::Appearance => appearance_tab(self.settings),
         };
 
         let content = container(
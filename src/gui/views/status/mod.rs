@@ -0,0 +1,314 @@
+use iced::{
+    widget::{button, container, horizontal_space, text, text_input, Column, Row},
+    Alignment, Element, Length,
+};
+use iced_graphics::Renderer;
+use iced_lazy::Component;
+use iced_native::{column, row};
+use time::{Duration, OffsetDateTime};
+
+use crate::{
+    data::{
+        state::State as AppState,
+        user::{CustomStatus, Presence},
+    },
+    gui::{
+        components::empty,
+        theme::{Button, Container, Text, Theme},
+    },
+};
+
+pub fn status_view<'a, Message>(
+    state: &'a AppState,
+    on_message: impl Fn(StatusViewMessage) -> Message + 'static,
+) -> StatusView<'a, Message> {
+    StatusView::new(state, on_message)
+}
+
+#[derive(Debug, Clone)]
+pub enum StatusViewMessage {
+    /// Sets the presence and custom status of the current user
+    SetStatus {
+        presence: Presence,
+        custom_status: Option<CustomStatus>,
+    },
+}
+
+/// How long a custom status is kept before discord clears it
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Expiry {
+    #[default]
+    Never,
+    HalfHour,
+    Hour,
+    FourHours,
+    Day,
+}
+
+impl Expiry {
+    const ALL: [Self; 5] = [
+        Self::Never,
+        Self::HalfHour,
+        Self::Hour,
+        Self::FourHours,
+        Self::Day,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Expiry::Never => "Don't clear",
+            Expiry::HalfHour => "30 minutes",
+            Expiry::Hour => "1 hour",
+            Expiry::FourHours => "4 hours",
+            Expiry::Day => "24 hours",
+        }
+    }
+
+    fn expires_at(self) -> Option<OffsetDateTime> {
+        let duration = match self {
+            Expiry::Never => return None,
+            Expiry::HalfHour => Duration::minutes(30),
+            Expiry::Hour => Duration::hours(1),
+            Expiry::FourHours => Duration::hours(4),
+            Expiry::Day => Duration::days(1),
+        };
+
+        Some(OffsetDateTime::now_utc() + duration)
+    }
+}
+
+#[derive(Default)]
+pub struct State {
+    /// Edited custom status text, `None` while the current one is unchanged
+    draft: Option<String>,
+    expiry: Expiry,
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    PresenceSelected(Presence),
+    DraftChanged(String),
+    ExpirySelected(Expiry),
+    SavePressed,
+    ClearPressed,
+}
+
+pub struct StatusView<'a, Message> {
+    state: &'a AppState,
+    on_message: Box<dyn Fn(StatusViewMessage) -> Message>,
+}
+
+impl<'a, Message> StatusView<'a, Message> {
+    fn new(
+        state: &'a AppState,
+        on_message: impl Fn(StatusViewMessage) -> Message + 'static,
+    ) -> Self {
+        Self {
+            state,
+            on_message: Box::new(on_message),
+        }
+    }
+
+    fn presence(&self) -> Presence {
+        self.state
+            .user_cache
+            .get(&self.state.user_id)
+            .map_or(Presence::Online, |u| u.presence)
+    }
+
+    fn set_status(&self, presence: Presence, custom_status: Option<CustomStatus>) -> Message {
+        (self.on_message)(StatusViewMessage::SetStatus {
+            presence,
+            custom_status,
+        })
+    }
+}
+
+impl<'a, Message, Backend> Component<Message, Renderer<Backend, Theme>> for StatusView<'a, Message>
+where
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    type State = State;
+    type Event = Event;
+
+    fn update(&mut self, state: &mut Self::State, event: Self::Event) -> Option<Message> {
+        match event {
+            Event::PresenceSelected(presence) => {
+                Some(self.set_status(presence, self.state.custom_status.clone()))
+            }
+            Event::DraftChanged(draft) => {
+                state.draft = Some(draft);
+                None
+            }
+            Event::ExpirySelected(expiry) => {
+                state.expiry = expiry;
+                None
+            }
+            Event::SavePressed => {
+                let text = match state.draft.take() {
+                    Some(draft) => draft.trim().to_string(),
+                    None => self
+                        .state
+                        .custom_status
+                        .as_ref()
+                        .map(|s| s.text.clone())
+                        .unwrap_or_default(),
+                };
+
+                let custom_status = (!text.is_empty()).then(|| CustomStatus {
+                    text,
+                    expires_at: state.expiry.expires_at(),
+                });
+
+                Some(self.set_status(self.presence(), custom_status))
+            }
+            Event::ClearPressed => {
+                state.draft = None;
+                Some(self.set_status(self.presence(), None))
+            }
+        }
+    }
+
+    fn view(&self, state: &Self::State) -> Element<'_, Self::Event, Renderer<Backend, Theme>> {
+        let current = self.presence();
+
+        let presences = Column::with_children(
+            [
+                Presence::Online,
+                Presence::Idle,
+                Presence::DoNotDisturb,
+                Presence::Offline,
+            ]
+            .into_iter()
+            .map(|presence| {
+                let label = if presence == Presence::Offline {
+                    String::from("Invisible")
+                } else {
+                    presence.to_string()
+                };
+
+                button(
+                    row![
+                        container(empty())
+                            .style(Container::Status(presence, 6.0))
+                            .width(Length::Units(12))
+                            .height(Length::Units(12)),
+                        text(label)
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                )
+                .style(Button::TransparentHover(presence == current, Some(5.0)))
+                .width(Length::Fill)
+                .padding([8, 12])
+                .on_press(Event::PresenceSelected(presence))
+                .into()
+            })
+            .collect(),
+        )
+        .spacing(5);
+
+        let current_text = self
+            .state
+            .custom_status
+            .as_ref()
+            .map(|s| s.text.as_str())
+            .unwrap_or("");
+
+        let expiry = Row::with_children(
+            Expiry::ALL
+                .into_iter()
+                .map(|expiry| {
+                    button(text(expiry.label()).size(14))
+                        .style(Button::TransparentHover(expiry == state.expiry, Some(5.0)))
+                        .padding([5, 10])
+                        .on_press(Event::ExpirySelected(expiry))
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let expires_at: Element<_, _> =
+            match self.state.custom_status.as_ref().and_then(|s| s.expires_at) {
+                Some(expires_at) => {
+                    let minutes = (expires_at - OffsetDateTime::now_utc())
+                        .whole_minutes()
+                        .max(0);
+                    text(format!("Clears in {}h {:02}m", minutes / 60, minutes % 60))
+                        .size(14)
+                        .style(Text::Weak)
+                        .into()
+                }
+                None => empty().into(),
+            };
+
+        let custom_status = container(
+            column![
+                text_input(
+                    "What's on your mind?",
+                    state.draft.as_deref().unwrap_or(current_text),
+                    Event::DraftChanged,
+                )
+                .on_submit(Event::SavePressed)
+                .padding(10),
+                text("Clear after").size(14).style(Text::Weak),
+                expiry,
+                row![
+                    expires_at,
+                    horizontal_space(Length::Fill),
+                    button("Clear")
+                        .style(Button::Secondary(None))
+                        .padding([5, 12])
+                        .on_press(Event::ClearPressed),
+                    button("Save")
+                        .style(Button::Primary(None))
+                        .padding([5, 12])
+                        .on_press(Event::SavePressed)
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center)
+            ]
+            .spacing(10),
+        )
+        .style(Container::BackgroundWeak(20.0))
+        .padding(20);
+
+        container(
+            column![
+                text("Status"),
+                container(presences)
+                    .style(Container::BackgroundWeak(20.0))
+                    .padding(12),
+                text("Custom Status"),
+                custom_status
+            ]
+            .spacing(15)
+            .max_width(500),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding([25, 20])
+        .center_x()
+        .into()
+    }
+}
+
+impl<'a, Message, Backend> From<StatusView<'a, Message>>
+    for Element<'a, Message, Renderer<Backend, Theme>>
+where
+    Message: 'a,
+    Backend: iced_graphics::Backend
+        + iced_graphics::backend::Text
+        + iced_graphics::backend::Image
+        + iced_graphics::backend::Svg
+        + 'static,
+{
+    fn from(status_view: StatusView<'a, Message>) -> Self {
+        iced_lazy::component(status_view)
+    }
+}
@@ -10,8 +10,15 @@ use tracing_subscriber::{fmt::time::UtcTime, FmtSubscriber};
 
 use gui::App;
 
+fn main() -> Result<()> {
+    // Has to happen before the runtime starts its threads
+    data::timestamp::init_local_offset();
+
+    run()
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn run() -> Result<()> {
     // Logging
     tracing::subscriber::set_global_default(
         FmtSubscriber::builder()
@@ -28,6 +35,7 @@ async fn main() -> Result<()> {
             min_size: Some((950, 600)),
             ..Default::default()
         },
+        default_font: Some(gui::fonts::REGULAR),
         ..Default::default()
     })?;
 